@@ -1,3 +1,16 @@
 pub mod grid;
+pub mod graph;
 pub mod bfs;
-pub mod runner;
\ No newline at end of file
+pub mod bisect;
+pub mod dag;
+pub mod runner;
+pub mod visited;
+pub mod shortest_path;
+pub mod union_find;
+pub mod cycle;
+pub mod memo;
+pub mod trie;
+pub mod math;
+pub mod linear;
+#[cfg(test)]
+pub mod bench;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Mean duration of `runs` calls to `f`, after one warm-up call
+pub fn mean_time<T>(runs: u32, mut f: impl FnMut() -> T) -> Duration {
+    black_box(f());
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(f());
+    }
+    start.elapsed() / runs
+}
+
+/// Time two implementations of the same computation and print both, checking first that they agree.
+/// For the ignored benchmark tests, run with `cargo test --release -- --ignored --nocapture`.
+pub fn compare<T: PartialEq + std::fmt::Debug>(
+    name: &str,
+    runs: u32,
+    (baseline_name, mut baseline): (&str, impl FnMut() -> T),
+    (candidate_name, mut candidate): (&str, impl FnMut() -> T),
+) -> (Duration, Duration) {
+    assert_eq!(baseline(), candidate(), "{name}: the implementations disagree");
+    let before = mean_time(runs, baseline);
+    let after = mean_time(runs, candidate);
+    println!(
+        "{name}: {baseline_name} {before:?}, {candidate_name} {after:?} ({:.2}x)",
+        before.as_secs_f64() / after.as_secs_f64()
+    );
+    (before, after)
+}
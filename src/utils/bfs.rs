@@ -1,17 +1,33 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
+use super::dag::enumerate_paths;
 use super::grid::Point;
+use super::visited::VisitedSet;
 
 /// Generic BFS function for finding shortest path between points
-pub fn find_shortest_path<F>(
+pub fn find_shortest_path<F, I>(
     start: Point,
     is_target: impl Fn(&Point) -> bool,
     get_neighbors: F,
 ) -> Option<usize>
 where
-    F: Fn(&Point) -> Vec<Point>,
+    F: Fn(&Point) -> I,
+    I: IntoIterator<Item = Point>,
+{
+    find_shortest_path_with(start, is_target, get_neighbors, &mut HashSet::new())
+}
+
+/// `find_shortest_path` with a caller-provided visited set, e.g. a `BitGrid` for bounded grids
+pub fn find_shortest_path_with<F, I>(
+    start: Point,
+    is_target: impl Fn(&Point) -> bool,
+    get_neighbors: F,
+    visited: &mut impl VisitedSet<Point>,
+) -> Option<usize>
+where
+    F: Fn(&Point) -> I,
+    I: IntoIterator<Item = Point>,
 {
-    let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
 
     queue.push_back((start, 0));
@@ -23,8 +39,7 @@ where
         }
 
         for next in get_neighbors(&pos) {
-            if !visited.contains(&next) {
-                visited.insert(next);
+            if visited.insert(next) {
                 queue.push_back((next, steps + 1));
             }
         }
@@ -33,11 +48,21 @@ where
 }
 
 /// Generic BFS function for finding all reachable positions
-pub fn find_reachable_positions<T: Hash + Eq + Copy>(
+pub fn find_reachable_positions<T: Hash + Eq + Copy, I: IntoIterator<Item = T>>(
     start: T,
-    get_neighbors: impl Fn(&T) -> Vec<T>,
+    get_neighbors: impl Fn(&T) -> I,
 ) -> HashSet<T> {
     let mut visited = HashSet::new();
+    find_reachable_positions_with(start, get_neighbors, &mut visited);
+    visited
+}
+
+/// `find_reachable_positions` that marks every reachable position in `visited`
+pub fn find_reachable_positions_with<T: Copy, I: IntoIterator<Item = T>>(
+    start: T,
+    get_neighbors: impl Fn(&T) -> I,
+    visited: &mut impl VisitedSet<T>,
+) {
     let mut queue = VecDeque::new();
 
     queue.push_back(start);
@@ -45,22 +70,28 @@ pub fn find_reachable_positions<T: Hash + Eq + Copy>(
 
     while let Some(pos) = queue.pop_front() {
         for next in get_neighbors(&pos) {
-            if !visited.contains(&next) {
-                visited.insert(next);
+            if visited.insert(next) {
                 queue.push_back(next);
             }
         }
     }
-    visited
 }
 
 /// BFS function that returns distances to all reachable positions
-pub fn get_distances<T: Hash + Eq + Copy>(
+pub fn get_distances<T: Hash + Eq + Copy, I: IntoIterator<Item = T>>(
     start: T,
-    get_neighbors: impl Fn(&T) -> Vec<T>,
+    get_neighbors: impl Fn(&T) -> I,
+) -> Vec<(T, usize)> {
+    get_distances_with(start, get_neighbors, &mut HashSet::new())
+}
+
+/// `get_distances` with a caller-provided visited set, e.g. a `BitGrid` for bounded grids
+pub fn get_distances_with<T: Copy, I: IntoIterator<Item = T>>(
+    start: T,
+    get_neighbors: impl Fn(&T) -> I,
+    visited: &mut impl VisitedSet<T>,
 ) -> Vec<(T, usize)> {
     let mut distances = Vec::new();
-    let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
 
     queue.push_back((start, 0));
@@ -70,8 +101,7 @@ pub fn get_distances<T: Hash + Eq + Copy>(
         distances.push((pos, dist));
         
         for next in get_neighbors(&pos) {
-            if !visited.contains(&next) {
-                visited.insert(next);
+            if visited.insert(next) {
                 queue.push_back((next, dist + 1));
             }
         }
@@ -79,29 +109,185 @@ pub fn get_distances<T: Hash + Eq + Copy>(
     distances
 }
 
-/// BFS function that allows collecting multiple paths
-pub fn find_all_paths<T: Hash + Eq + Copy>(
+/// All simple paths from `start` to a target, shortest first.
+/// Use `dag::enumerate_paths` to walk them lazily instead of collecting them.
+pub fn find_all_paths<T: Hash + Eq + Copy, I: IntoIterator<Item = T>>(
     start: T,
     is_target: impl Fn(&T) -> bool,
-    get_neighbors: impl Fn(&T) -> Vec<T>,
+    get_neighbors: impl Fn(&T) -> I,
 ) -> Vec<Vec<T>> {
-    let mut paths = Vec::new();
+    let mut paths: Vec<_> = enumerate_paths(start, is_target, get_neighbors).collect();
+    paths.sort_by_key(Vec::len);
+    paths
+}
+
+/// States reached by a breadth-first search, with their distance and the state they were reached from
+#[derive(Debug, Clone)]
+pub struct SearchTree<T> {
+    nodes: HashMap<T, (usize, Option<T>)>,
+}
+
+impl<T: Hash + Eq + Clone> SearchTree<T> {
+    pub fn contains(&self, state: &T) -> bool {
+        self.nodes.contains_key(state)
+    }
+
+    /// Number of steps from the nearest start
+    pub fn distance(&self, state: &T) -> Option<usize> {
+        self.nodes.get(state).map(|(dist, _)| *dist)
+    }
+
+    /// State from which `state` was first reached, `None` for starts and unreached states
+    pub fn parent(&self, state: &T) -> Option<&T> {
+        self.nodes.get(state)?.1.as_ref()
+    }
+
+    /// Shortest path from one of the starts to `state`, both included
+    pub fn path_to(&self, state: &T) -> Option<Vec<T>> {
+        let mut path = vec![state.clone()];
+        let mut current = self.nodes.get(state)?;
+        while let (_, Some(parent)) = current {
+            path.push(parent.clone());
+            current = &self.nodes[parent];
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Iterate over reached states and their distances
+    pub fn distances(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.nodes.iter().map(|(state, (dist, _))| (state, *dist))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// BFS from all starts at once, stopping at the first state matching `is_target`.
+/// Returns the reached target together with the search tree built so far.
+fn search<T, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+) -> (Option<T>, SearchTree<T>)
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let mut nodes = HashMap::new();
     let mut queue = VecDeque::new();
-    queue.push_back((start, vec![start]));
 
-    while let Some((pos, path)) = queue.pop_front() {
-        if is_target(&pos) {
-            paths.push(path);
-            continue;
+    for start in starts {
+        if !nodes.contains_key(&start) {
+            nodes.insert(start.clone(), (0, None));
+            queue.push_back((start, 0));
         }
+    }
 
-        for next in get_neighbors(&pos) {
-            if !path.contains(&next) {
-                let mut new_path = path.clone();
-                new_path.push(next);
-                queue.push_back((next, new_path));
+    while let Some((state, dist)) = queue.pop_front() {
+        if is_target(&state) {
+            return (Some(state), SearchTree { nodes });
+        }
+
+        for next in get_neighbors(&state) {
+            if !nodes.contains_key(&next) {
+                nodes.insert(next.clone(), (dist + 1, Some(state.clone())));
+                queue.push_back((next, dist + 1));
             }
         }
     }
-    paths
+    (None, SearchTree { nodes })
+}
+
+/// Shortest path from any of the starts to the nearest state matching `is_target`.
+/// Returns the number of steps and the states along the path, both ends included.
+pub fn bfs_path<T, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+) -> Option<(usize, Vec<T>)>
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let (target, tree) = search(starts, is_target, get_neighbors);
+    let path = tree.path_to(&target?)?;
+    Some((path.len() - 1, path))
+}
+
+/// Explore everything reachable from the starts, keeping distances and parents
+pub fn bfs_tree<T, I>(
+    starts: impl IntoIterator<Item = T>,
+    get_neighbors: impl Fn(&T) -> I,
+) -> SearchTree<T>
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    search(starts, |_| false, get_neighbors).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Numbers reachable by `+1` or `*2`
+    fn steps(n: &u32) -> [u32; 2] {
+        [n + 1, n * 2]
+    }
+
+    #[test]
+    fn test_bfs_path() {
+        let (dist, path) = bfs_path([1], |&n| n == 10, steps).unwrap();
+        assert_eq!(dist, 4);
+        assert_eq!(path.len(), 5);
+        assert_eq!((path[0], path[4]), (1, 10));
+        assert!(path.windows(2).all(|w| steps(&w[0]).contains(&w[1])));
+
+        assert_eq!(bfs_path([3], |&n| n == 3, steps), Some((0, vec![3])));
+        let bounded = |n: &u32| steps(n).into_iter().filter(|&m| m <= 100);
+        assert_eq!(bfs_path([5], |&n| n < 5, bounded), None);
+    }
+
+    #[test]
+    fn test_bfs_multiple_starts() {
+        // path from the closer of two starts
+        let (dist, path) = bfs_path([1, 9], |&n| n == 18, steps).unwrap();
+        assert_eq!((dist, path), (1, vec![9, 18]));
+    }
+
+    #[test]
+    fn test_bfs_on_string_states() {
+        let words = ["cold", "cord", "card", "ward", "warm", "word", "worm"];
+        let one_letter_apart = |a: &String| {
+            words.iter()
+                .filter(|b| a.chars().zip(b.chars()).filter(|(x, y)| x != y).count() == 1)
+                .map(|b| b.to_string())
+                .collect::<Vec<_>>()
+        };
+        let (dist, path) = bfs_path(["cold".to_string()], |w| w == "warm", one_letter_apart).unwrap();
+        assert_eq!(dist, 4);
+        assert_eq!(path, ["cold", "cord", "card", "ward", "warm"]);
+    }
+
+    #[test]
+    fn test_bfs_tree() {
+        let tree = bfs_tree([Point::new(0, 0)], |p| {
+            p.neighbors_with_bounds(3, 3).filter(|n| *n != Point::new(1, 1))
+        });
+        assert_eq!(tree.len(), 8);
+        assert!(!tree.contains(&Point::new(1, 1)));
+        assert_eq!(tree.distance(&Point::new(2, 2)), Some(4));
+        assert_eq!(tree.parent(&Point::new(0, 0)), None);
+
+        let path = tree.path_to(&Point::new(2, 2)).unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(path.windows(2).all(|w| w[0].manhattan_distance(&w[1]) == 1));
+        assert_eq!(tree.distances().map(|(_, d)| d).max(), Some(4));
+    }
 }
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+/// Integer types that can be bisected
+pub trait Bisect: Copy + Ord + Hash {
+    const ONE: Self;
+    const MAX: Self;
+
+    /// Midpoint of `lo..hi` without overflow, assuming `lo < hi`
+    fn midpoint(lo: Self, hi: Self) -> Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_bisect {
+    ($($t:ty => $unsigned:ty),*) => {
+        $(impl Bisect for $t {
+            const ONE: Self = 1;
+            const MAX: Self = <$t>::MAX;
+
+            fn midpoint(lo: Self, hi: Self) -> Self {
+                // the gap may not fit in a signed type, but half of it always does
+                let half = (hi as $unsigned).wrapping_sub(lo as $unsigned) / 2;
+                lo.wrapping_add(half as $t)
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+        })*
+    };
+}
+
+impl_bisect!(u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize, i32 => u32, i64 => u64, isize => usize);
+
+/// First value in `range` where a monotone predicate (false, ..., false, true, ..., true) holds,
+/// or `range.end` if it never does. Evaluates the predicate O(log n) times.
+pub fn partition_point<T: Bisect>(range: Range<T>, mut pred: impl FnMut(T) -> bool) -> T {
+    let (mut lo, mut hi) = (range.start, range.end);
+    while lo < hi {
+        let mid = T::midpoint(lo, hi);
+        if pred(mid) {
+            hi = mid;
+        } else {
+            lo = mid.checked_add(T::ONE).unwrap();
+        }
+    }
+    lo
+}
+
+/// First value in `range` where a monotone predicate holds
+pub fn first_true<T: Bisect>(range: Range<T>, pred: impl FnMut(T) -> bool) -> Option<T> {
+    let end = range.end;
+    let found = partition_point(range, pred);
+    (found < end).then_some(found)
+}
+
+/// Index of the first element of `slice` where a monotone predicate holds
+pub fn first_true_in<T>(slice: &[T], mut pred: impl FnMut(&T) -> bool) -> Option<usize> {
+    first_true(0..slice.len(), |i| pred(&slice[i]))
+}
+
+/// First value from `start` upwards where a monotone predicate holds, for searches without a known end.
+/// Probes `start`, `start + 1`, `start + 3`, `start + 7`, ... until the predicate holds, then bisects
+/// the last gap. A probe past the end of the type is made at its largest value instead;
+/// returns `None` if the predicate doesn't hold there either.
+pub fn first_true_from<T: Bisect>(start: T, mut pred: impl FnMut(T) -> bool) -> Option<T> {
+    if pred(start) {
+        return Some(start);
+    }
+    let (mut lo, mut step) = (start, T::ONE);
+    while lo < T::MAX {
+        let hi = lo.checked_add(step).unwrap_or(T::MAX);
+        if pred(hi) {
+            // `lo` is known to be false and `hi` true
+            return Some(partition_point(lo.checked_add(T::ONE).unwrap()..hi, &mut pred));
+        }
+        lo = hi;
+        step = step.checked_add(step).unwrap_or(T::MAX);
+    }
+    None
+}
+
+/// Predicate wrapper that remembers every result, for expensive predicates
+/// queried by several searches or re-checked afterwards
+pub struct CachedPredicate<T, F> {
+    pred: F,
+    cache: HashMap<T, bool>,
+}
+
+impl<T: Hash + Eq + Copy, F: FnMut(T) -> bool> CachedPredicate<T, F> {
+    pub fn new(pred: F) -> Self {
+        Self { pred, cache: HashMap::new() }
+    }
+
+    pub fn get(&mut self, x: T) -> bool {
+        if let Some(&result) = self.cache.get(&x) {
+            return result;
+        }
+        let result = (self.pred)(x);
+        self.cache.insert(x, result);
+        result
+    }
+
+    /// Number of times the wrapped predicate was actually called
+    pub fn evaluations(&self) -> usize {
+        self.cache.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_true() {
+        assert_eq!(first_true(0..100, |x: i32| x * x >= 50), Some(8));
+        assert_eq!(first_true(0..5, |x: i32| x >= 0), Some(0));
+        assert_eq!(first_true(0..5, |x: i32| x >= 5), None);
+        assert_eq!(first_true(3..3, |_: u32| true), None);
+        assert_eq!(partition_point(0..5, |x: u8| x > 9), 5);
+        assert_eq!(first_true(-50..50, |x: i64| x > -7), Some(-6));
+        assert_eq!(first_true(0..u64::MAX, |x| x >= u64::MAX - 1), Some(u64::MAX - 1));
+        // ranges wider than the signed maximum
+        assert_eq!(first_true(i32::MIN..i32::MAX, |x| x >= 12345), Some(12345));
+        assert_eq!(first_true(i64::MIN..i64::MAX, |x| x > i64::MIN), Some(i64::MIN + 1));
+        assert_eq!(first_true(i32::MIN..i32::MAX, |x| x >= i32::MAX - 1), Some(i32::MAX - 1));
+    }
+
+    #[test]
+    fn test_first_true_in_slice() {
+        let times = [1, 3, 3, 8, 12, 20];
+        assert_eq!(first_true_in(&times, |&t| t >= 3), Some(1));
+        assert_eq!(first_true_in(&times, |&t| t > 12), Some(5));
+        assert_eq!(first_true_in(&times, |&t| t > 20), None);
+        assert_eq!(first_true_in(&[] as &[i32], |_| true), None);
+    }
+
+    #[test]
+    fn test_first_true_from() {
+        let mut calls = 0;
+        let found = first_true_from(0u64, |x| {
+            calls += 1;
+            x >= 1_000_000
+        });
+        assert_eq!(found, Some(1_000_000));
+        assert!(calls < 50);
+
+        assert_eq!(first_true_from(10i32, |x| x >= 3), Some(10));
+        assert_eq!(first_true_from(10i32, |x| x >= 11), Some(11));
+        assert_eq!(first_true_from(0u8, |_| false), None);
+
+        // the doubling steps overflow before reaching the answer
+        assert_eq!(first_true_from(100u8, |x| x >= 250), Some(250));
+        assert_eq!(first_true_from(100u8, |x| x == u8::MAX), Some(u8::MAX));
+        assert_eq!(first_true_from(i32::MAX - 10, |x| x >= i32::MAX - 1), Some(i32::MAX - 1));
+        assert_eq!(first_true_from(u8::MAX, |_| false), None);
+    }
+
+    #[test]
+    fn test_cached_predicate() {
+        let mut calls = 0;
+        let mut expensive = CachedPredicate::new(|x: usize| {
+            calls += 1;
+            x >= 37
+        });
+        assert_eq!(first_true(0..1000, |x| expensive.get(x)), Some(37));
+        let first_search = expensive.evaluations();
+        // a second search over a subrange reuses the results
+        assert_eq!(first_true(0..500, |x| expensive.get(x)), Some(37));
+        assert!(expensive.get(37) && !expensive.get(36));
+        assert!(expensive.evaluations() <= first_search + 2);
+        drop(expensive);
+        assert!(calls <= 13);
+    }
+}
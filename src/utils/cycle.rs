@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Shape of the sequence `x0, step(x0), step(step(x0)), ...` of a deterministic simulation:
+/// states from index `start` on repeat every `length` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Earliest step index whose state equals the state at step `n`
+    pub fn earliest_equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Find the cycle by remembering the index of every state seen.
+/// Uses O(start + length) memory; loops forever if no state repeats.
+pub fn find_cycle<T: Hash + Eq + Clone>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for index in 0.. {
+        if let Some(start) = seen.insert(state.clone(), index) {
+            return Cycle { start, length: index - start };
+        }
+        state = step(&state);
+    }
+    unreachable!()
+}
+
+/// Find the cycle with Brent's algorithm, which only needs to compare states
+/// and keeps two of them in memory. Loops forever if no state repeats.
+pub fn find_cycle_brent<T: PartialEq + Clone>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    detect_cycle(initial, |state| Some(step(state))).unwrap()
+}
+
+/// Brent's algorithm for simulations that may end: `step` returns `None` once there is
+/// no next state, in which case there is no cycle.
+pub fn detect_cycle<T: PartialEq + Clone>(initial: T, mut step: impl FnMut(&T) -> Option<T>) -> Option<Cycle> {
+    // find the cycle length by moving the tortoise to the hare at every power of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    // with the hare `length` steps ahead, both meet at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+/// State after `n` steps, simulating only until the first repeated state
+pub fn state_at<T: Hash + Eq + Clone>(initial: T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut history = vec![initial];
+    while history.len() <= n {
+        let next = step(history.last().unwrap());
+        if let Some(&start) = seen.get(&next) {
+            let cycle = Cycle { start, length: history.len() - start };
+            return history.swap_remove(cycle.earliest_equivalent(n));
+        }
+        seen.insert(next.clone(), history.len());
+        history.push(next);
+    }
+    history.swap_remove(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 3 -> ...
+    fn rho(&x: &u32) -> u32 {
+        if x == 6 { 3 } else { x + 1 }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let expected = Cycle { start: 3, length: 4 };
+        assert_eq!(find_cycle(0, rho), expected);
+        assert_eq!(find_cycle_brent(0, rho), expected);
+        assert_eq!(find_cycle(5, rho), Cycle { start: 0, length: 4 });
+        assert_eq!(find_cycle_brent(5, rho), Cycle { start: 0, length: 4 });
+        assert_eq!(find_cycle_brent(7, |&x: &u32| x), Cycle { start: 0, length: 1 });
+    }
+
+    #[test]
+    fn test_algorithms_agree() {
+        // x -> x^2 + 1 mod m has a rho shape for every start
+        for m in [97u64, 1000, 4099] {
+            for start in [0, 2, 42] {
+                let step = |&x: &u64| (x * x + 1) % m;
+                assert_eq!(find_cycle(start, step), find_cycle_brent(start, step));
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_cycle_in_finite_simulation() {
+        assert_eq!(detect_cycle(0, |&x: &u32| (x < 10).then_some(x + 1)), None);
+        assert_eq!(detect_cycle(0, |&x: &u32| (x < 10).then_some(rho(&x))), Some(Cycle { start: 3, length: 4 }));
+    }
+
+    #[test]
+    fn test_state_at() {
+        assert_eq!(state_at(0, rho, 2), 2);
+        assert_eq!(state_at(0, rho, 7), 3);
+        assert_eq!(state_at(0, rho, 1_000_000_000), 3 + (1_000_000_000 - 3) % 4);
+
+        // a rotating string after a billion rotations
+        let rotate = |s: &String| format!("{}{}", &s[1..], &s[..1]);
+        assert_eq!(state_at("abcde".to_string(), rotate, 1_000_000_002), "cdeab");
+
+        let cycle = Cycle { start: 3, length: 4 };
+        assert_eq!(cycle.earliest_equivalent(2), 2);
+        assert_eq!(cycle.earliest_equivalent(11), 3);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Number of distinct paths from any of the starts to a target state.
+/// Targets end a path, and counts are memoized per state, so the state space must be acyclic
+/// (e.g. moves to strictly increasing heights). Panics if a cycle is found.
+pub fn count_paths<T, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+) -> usize
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let mut memo = HashMap::new();
+    starts
+        .into_iter()
+        .map(|start| paths_from(start, &is_target, &get_neighbors, &mut memo))
+        .sum()
+}
+
+fn paths_from<T, I>(
+    start: T,
+    is_target: &impl Fn(&T) -> bool,
+    get_neighbors: &impl Fn(&T) -> I,
+    memo: &mut HashMap<T, usize>,
+) -> usize
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let mut in_progress = HashSet::new();
+    // post-order walk: a state is counted once all of its neighbors are
+    let mut stack = vec![(start.clone(), false)];
+    while let Some((state, expanded)) = stack.pop() {
+        if memo.contains_key(&state) {
+            continue;
+        }
+        if is_target(&state) {
+            memo.insert(state, 1);
+        } else if expanded {
+            let total = get_neighbors(&state).into_iter().map(|next| memo[&next]).sum();
+            in_progress.remove(&state);
+            memo.insert(state, total);
+        } else {
+            assert!(in_progress.insert(state.clone()), "path counting requires an acyclic state space");
+            stack.push((state.clone(), true));
+            stack.extend(
+                get_neighbors(&state)
+                    .into_iter()
+                    .filter(|next| !memo.contains_key(next))
+                    .map(|next| (next, false)),
+            );
+        }
+    }
+    memo[&start]
+}
+
+/// Number of distinct target states reachable from `start`; targets are not expanded further
+pub fn count_reachable_targets<T, I>(
+    start: T,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+) -> usize
+where
+    T: Hash + Eq + Clone,
+    I: IntoIterator<Item = T>,
+{
+    let mut visited = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+    let mut count = 0;
+    while let Some(state) = stack.pop() {
+        if is_target(&state) {
+            count += 1;
+            continue;
+        }
+        for next in get_neighbors(&state) {
+            if visited.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+    count
+}
+
+/// Lazily enumerate the paths from `start` to a target, depth first.
+/// Targets end a path, and states already on the current path are skipped,
+/// so this also yields the simple paths of graphs with cycles.
+pub fn enumerate_paths<T, P, F, I>(start: T, is_target: P, get_neighbors: F) -> Paths<T, P, F, I::IntoIter>
+where
+    T: PartialEq + Clone,
+    P: Fn(&T) -> bool,
+    F: Fn(&T) -> I,
+    I: IntoIterator<Item = T>,
+{
+    let (first, pending) = if is_target(&start) {
+        (Some(vec![start.clone()]), Vec::new())
+    } else {
+        (None, vec![get_neighbors(&start).into_iter()])
+    };
+    Paths { is_target, get_neighbors, first, path: vec![start], pending }
+}
+
+/// Iterator returned by `enumerate_paths`.
+/// Only the current path and the unexplored neighbors along it are kept in memory.
+pub struct Paths<T, P, F, N> {
+    is_target: P,
+    get_neighbors: F,
+    first: Option<Vec<T>>,
+    path: Vec<T>,
+    /// Remaining neighbors of each state on `path`
+    pending: Vec<N>,
+}
+
+impl<T, P, F, I, N> Iterator for Paths<T, P, F, N>
+where
+    T: PartialEq + Clone,
+    P: Fn(&T) -> bool,
+    F: Fn(&T) -> I,
+    I: IntoIterator<Item = T, IntoIter = N>,
+    N: Iterator<Item = T>,
+{
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if let Some(path) = self.first.take() {
+            return Some(path);
+        }
+        while let Some(neighbors) = self.pending.last_mut() {
+            let Some(next) = neighbors.next() else {
+                self.pending.pop();
+                self.path.pop();
+                continue;
+            };
+            if self.path.contains(&next) {
+                continue;
+            }
+            if (self.is_target)(&next) {
+                let mut found = self.path.clone();
+                found.push(next);
+                return Some(found);
+            }
+            self.pending.push((self.get_neighbors)(&next).into_iter());
+            self.path.push(next);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // moves right or down on a 3x3 square
+    fn right_or_down(&(x, y): &(u32, u32)) -> Vec<(u32, u32)> {
+        [(x + 1, y), (x, y + 1)].into_iter().filter(|&(x, y)| x < 3 && y < 3).collect()
+    }
+
+    #[test]
+    fn test_count_paths() {
+        assert_eq!(count_paths([(0, 0)], |&p| p == (2, 2), right_or_down), 6);
+        assert_eq!(count_paths([(0, 0), (1, 1)], |&p| p == (2, 2), right_or_down), 8);
+        // every cell in the last column is a target
+        assert_eq!(count_paths([(0, 0)], |&(x, _)| x == 2, right_or_down), 6);
+        assert_eq!(count_paths([(0, 0)], |_: &(u32, u32)| false, right_or_down), 0);
+    }
+
+    #[test]
+    fn test_count_paths_scales() {
+        // a 30x30 square has 58 choose 29 paths, far too many to enumerate
+        let moves = |&(x, y): &(u64, u64)| [(x + 1, y), (x, y + 1)].into_iter().filter(|&(x, y)| x < 30 && y < 30);
+        assert_eq!(count_paths([(0, 0)], |&p| p == (29, 29), moves), 30067266499541040);
+    }
+
+    #[test]
+    #[should_panic(expected = "acyclic")]
+    fn test_count_paths_rejects_cycles() {
+        count_paths([0], |&n| n == 3, |&n: &u32| vec![(n + 1) % 3]);
+    }
+
+    #[test]
+    fn test_count_reachable_targets() {
+        assert_eq!(count_reachable_targets((0, 0), |&p| p == (2, 2), right_or_down), 1);
+        assert_eq!(count_reachable_targets((0, 0), |&(x, _)| x == 2, right_or_down), 3);
+        assert_eq!(count_reachable_targets((1, 0), |&(x, _)| x == 0, right_or_down), 0);
+    }
+
+    #[test]
+    fn test_enumerate_paths() {
+        let paths: Vec<_> = enumerate_paths((0, 0), |&p| p == (2, 2), right_or_down).collect();
+        assert_eq!(paths.len(), 6);
+        assert_eq!(paths[0], vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]);
+        assert!(paths.iter().all(|path| path.len() == 5));
+
+        assert_eq!(enumerate_paths((2, 2), |&p| p == (2, 2), right_or_down).collect::<Vec<_>>(), vec![vec![(2, 2)]]);
+        // lazily take the first path out of a huge number
+        let moves = |&(x, y): &(u64, u64)| [(x + 1, y), (x, y + 1)].into_iter().filter(|&(x, y)| x < 30 && y < 30);
+        assert_eq!(enumerate_paths((0, 0), |&p| p == (29, 29), moves).next().unwrap().len(), 59);
+    }
+
+    #[test]
+    fn test_enumerate_simple_paths_with_cycles() {
+        // square 0-1-2-3-0, from 0 to 2
+        let neighbors = |&n: &u32| [(n + 1) % 4, (n + 3) % 4];
+        let paths: Vec<_> = enumerate_paths(0, |&n| n == 2, neighbors).collect();
+        assert_eq!(paths, vec![vec![0, 1, 2], vec![0, 3, 2]]);
+    }
+}
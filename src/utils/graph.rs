@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Range;
+
+mod clique;
+mod interner;
+mod topo;
+
+pub use clique::MaximalCliques;
+pub use interner::Interner;
+pub use topo::Cycle;
+
+/// Graph over named nodes stored as adjacency maps on dense integer ids.
+/// Undirected graphs store every edge in both directions.
+/// `W` is the edge weight, `()` for unweighted graphs.
+#[derive(Debug, Clone)]
+pub struct Graph<N, W = ()> {
+    names: Interner<N>,
+    edges: Vec<BTreeMap<usize, W>>,
+    directed: bool,
+}
+
+/// Degree distribution of a graph, using out-degrees for directed graphs
+#[derive(Debug, Clone, PartialEq)]
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// Number of nodes with each degree
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+impl<N: Hash + Eq + Clone, W: Clone> Graph<N, W> {
+    pub fn undirected() -> Self {
+        Self { names: Interner::new(), edges: Vec::new(), directed: false }
+    }
+
+    pub fn directed() -> Self {
+        Self { names: Interner::new(), edges: Vec::new(), directed: true }
+    }
+
+    /// Build a graph from `(from, to, weight)` triples
+    pub fn from_weighted_edges(directed: bool, edges: impl IntoIterator<Item = (N, N, W)>) -> Self {
+        let mut graph = if directed { Self::directed() } else { Self::undirected() };
+        for (a, b, weight) in edges {
+            graph.add_edge(a, b, weight);
+        }
+        graph
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Id of `name`, adding it as an isolated node if it is new
+    pub fn add_node(&mut self, name: N) -> usize {
+        let id = self.names.intern(name);
+        if id == self.edges.len() {
+            self.edges.push(BTreeMap::new());
+        }
+        id
+    }
+
+    /// Add or reweight an edge between two named nodes, returning their ids
+    pub fn add_edge(&mut self, a: N, b: N, weight: W) -> (usize, usize) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        self.add_edge_ids(a, b, weight);
+        (a, b)
+    }
+
+    pub fn add_edge_ids(&mut self, a: usize, b: usize, weight: W) {
+        if !self.directed {
+            self.edges[b].insert(a, weight.clone());
+        }
+        self.edges[a].insert(b, weight);
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Number of edges, counting each undirected edge once
+    pub fn edge_count(&self) -> usize {
+        let stored: usize = self.edges.iter().map(BTreeMap::len).sum();
+        if self.directed {
+            stored
+        } else {
+            let loops = self.nodes().filter(|&id| self.has_edge(id, id)).count();
+            (stored + loops) / 2
+        }
+    }
+
+    /// All node ids
+    pub fn nodes(&self) -> Range<usize> {
+        0..self.edges.len()
+    }
+
+    pub fn names(&self) -> &Interner<N> {
+        &self.names
+    }
+
+    pub fn id(&self, name: &N) -> Option<usize> {
+        self.names.id(name)
+    }
+
+    pub fn name(&self, id: usize) -> &N {
+        self.names.name(id)
+    }
+
+    /// Out-neighbors of a node, in increasing id order
+    pub fn neighbors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.edges[id].keys().copied()
+    }
+
+    /// Out-edges of a node with their weights, in increasing id order
+    pub fn edges_from(&self, id: usize) -> impl Iterator<Item = (usize, &W)> + '_ {
+        self.edges[id].iter().map(|(&to, weight)| (to, weight))
+    }
+
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.edges[a].contains_key(&b)
+    }
+
+    /// Whether there is an edge between two named nodes, `false` if either is unknown
+    pub fn adjacent(&self, a: &N, b: &N) -> bool {
+        match (self.id(a), self.id(b)) {
+            (Some(a), Some(b)) => self.has_edge(a, b),
+            _ => false,
+        }
+    }
+
+    pub fn weight(&self, a: usize, b: usize) -> Option<&W> {
+        self.edges[a].get(&b)
+    }
+
+    /// Number of out-edges, which is the degree for undirected graphs
+    pub fn degree(&self, id: usize) -> usize {
+        self.edges[id].len()
+    }
+
+    /// Number of edges into every node
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.len()];
+        for to in self.edges.iter().flat_map(BTreeMap::keys) {
+            degrees[*to] += 1;
+        }
+        degrees
+    }
+
+    /// `None` for an empty graph
+    pub fn degree_stats(&self) -> Option<DegreeStats> {
+        let degrees: Vec<usize> = self.nodes().map(|id| self.degree(id)).collect();
+        let mut histogram = BTreeMap::new();
+        for &degree in &degrees {
+            *histogram.entry(degree).or_insert(0) += 1;
+        }
+        Some(DegreeStats {
+            min: *degrees.iter().min()?,
+            max: *degrees.iter().max()?,
+            mean: degrees.iter().sum::<usize>() as f64 / degrees.len() as f64,
+            histogram,
+        })
+    }
+
+    /// Node ids grouped by connected component, ignoring edge directions.
+    /// Components are ordered by their smallest id, and ids within them are sorted.
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let mut undirected = vec![Vec::new(); self.len()];
+        for a in self.nodes() {
+            for b in self.neighbors(a) {
+                undirected[a].push(b);
+                if self.directed {
+                    undirected[b].push(a);
+                }
+            }
+        }
+
+        let mut component = vec![usize::MAX; self.len()];
+        let mut components = Vec::new();
+        for start in self.nodes() {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            let label = components.len();
+            component[start] = label;
+            let mut members = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                for &next in &undirected[node] {
+                    if component[next] == usize::MAX {
+                        component[next] = label;
+                        members.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            members.sort_unstable();
+            components.push(members);
+        }
+        components
+    }
+
+    /// Graph with only the given nodes and the edges between them.
+    /// Ids are reassigned in the order the nodes are given; names are kept.
+    pub fn induced_subgraph(&self, nodes: impl IntoIterator<Item = usize>) -> Self {
+        let mut subgraph = if self.directed { Self::directed() } else { Self::undirected() };
+        let mut new_ids = BTreeMap::new();
+        for id in nodes {
+            new_ids.insert(id, subgraph.add_node(self.name(id).clone()));
+        }
+        for (&old, &new) in &new_ids {
+            for (to, weight) in self.edges_from(old) {
+                if let Some(&new_to) = new_ids.get(&to) {
+                    subgraph.edges[new].insert(new_to, weight.clone());
+                }
+            }
+        }
+        subgraph
+    }
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    /// Build an unweighted graph from `(from, to)` pairs
+    pub fn from_edges(directed: bool, edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        Self::from_weighted_edges(directed, edges.into_iter().map(|(a, b)| (a, b, ())))
+    }
+}
+
+impl Graph<String> {
+    /// Parse one edge per line written as two names around `separator`, e.g. `"kh-tc"`
+    pub fn parse(input: &str, separator: char, directed: bool) -> Self {
+        Self::from_edges(
+            directed,
+            input.lines().filter(|line| !line.trim().is_empty()).map(|line| {
+                let (a, b) = line.trim().split_once(separator).expect("Invalid edge format");
+                (a.to_string(), b.to_string())
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_undirected() {
+        let graph = Graph::parse("a-b\nb-c\nc-a\nd-e\n", '-', false);
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.edge_count(), 4);
+        assert!(graph.adjacent(&"b".to_string(), &"a".to_string()));
+        assert!(!graph.adjacent(&"a".to_string(), &"d".to_string()));
+        assert!(!graph.adjacent(&"a".to_string(), &"z".to_string()));
+
+        let a = graph.id(&"a".to_string()).unwrap();
+        assert_eq!(graph.neighbors(a).map(|id| graph.name(id).as_str()).collect::<Vec<_>>(), ["b", "c"]);
+        assert_eq!(graph.connected_components(), vec![vec![0, 1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn test_directed_weighted() {
+        let graph = Graph::from_weighted_edges(true, [(47, 53, 2), (97, 47, 5), (75, 53, 1)]);
+        assert!(graph.is_directed());
+        assert_eq!(graph.edge_count(), 3);
+        let (a, b) = (graph.id(&47).unwrap(), graph.id(&53).unwrap());
+        assert!(graph.has_edge(a, b));
+        assert!(!graph.has_edge(b, a));
+        assert_eq!(graph.weight(a, b), Some(&2));
+        assert_eq!(graph.in_degrees()[b], 2);
+        // directed edges still join components
+        assert_eq!(graph.connected_components().len(), 1);
+    }
+
+    #[test]
+    fn test_degree_stats() {
+        let graph = Graph::from_edges(false, [(0, 1), (0, 2), (0, 3), (1, 2)]);
+        let stats = graph.degree_stats().unwrap();
+        assert_eq!((stats.min, stats.max), (1, 3));
+        assert_eq!(stats.mean, 2.0);
+        assert_eq!(stats.histogram, BTreeMap::from([(1, 1), (2, 2), (3, 1)]));
+        assert!(Graph::<u8>::undirected().degree_stats().is_none());
+    }
+
+    #[test]
+    fn test_induced_subgraph() {
+        let graph = Graph::from_edges(false, [('a', 'b'), ('b', 'c'), ('c', 'd'), ('d', 'a')]);
+        let ids = ['b', 'c', 'd'].map(|name| graph.id(&name).unwrap());
+        let path = graph.induced_subgraph(ids);
+        assert_eq!(path.len(), 3);
+        assert_eq!(path.edge_count(), 2);
+        assert_eq!(*path.name(0), 'b');
+        assert!(path.adjacent(&'b', &'c'));
+        assert!(!path.adjacent(&'b', &'d'));
+        assert!(path.id(&'a').is_none());
+    }
+
+    #[test]
+    fn test_self_loop() {
+        let graph = Graph::from_edges(false, [(1, 1), (1, 2)]);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.degree(graph.id(&1).unwrap()), 2);
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::hash::Hash;
+
+use super::Graph;
+
+/// Clique queries for undirected graphs without self-loops.
+/// Cliques are returned as sorted node ids.
+impl<N: Hash + Eq + Clone, W: Clone> Graph<N, W> {
+    /// Every clique of exactly `k` nodes, each listed once
+    pub fn k_cliques(&self, k: usize) -> Vec<Vec<usize>> {
+        let mut cliques = Vec::new();
+        if k == 0 {
+            return cliques;
+        }
+        let mut clique = Vec::with_capacity(k);
+        for v in self.nodes() {
+            let later: Vec<usize> = self.neighbors(v).filter(|&u| u > v).collect();
+            clique.push(v);
+            self.extend_k_clique(&mut clique, &later, k, &mut cliques);
+            clique.pop();
+        }
+        cliques
+    }
+
+    fn extend_k_clique(&self, clique: &mut Vec<usize>, candidates: &[usize], k: usize, cliques: &mut Vec<Vec<usize>>) {
+        if clique.len() == k {
+            cliques.push(clique.clone());
+            return;
+        }
+        for (i, &v) in candidates.iter().enumerate() {
+            let next: Vec<usize> = candidates[i + 1..].iter().copied().filter(|&u| self.has_edge(v, u)).collect();
+            if clique.len() + 1 + next.len() < k {
+                continue;
+            }
+            clique.push(v);
+            self.extend_k_clique(clique, &next, k, cliques);
+            clique.pop();
+        }
+    }
+
+    /// `k_cliques` that contain at least one node whose name matches `predicate`
+    pub fn k_cliques_where(&self, k: usize, predicate: impl Fn(&N) -> bool) -> Vec<Vec<usize>> {
+        let mut cliques = self.k_cliques(k);
+        cliques.retain(|clique| self.clique_contains(clique, &predicate));
+        cliques
+    }
+
+    /// Whether any node of the clique has a name matching `predicate`
+    pub fn clique_contains(&self, clique: &[usize], predicate: impl Fn(&N) -> bool) -> bool {
+        clique.iter().any(|&id| predicate(self.name(id)))
+    }
+
+    /// Lazily enumerate the maximal cliques (Bron–Kerbosch with pivoting)
+    pub fn maximal_cliques(&self) -> MaximalCliques<'_, N, W> {
+        let candidates: BTreeSet<usize> = self.nodes().collect();
+        let excluded = BTreeSet::new();
+        let stack = if candidates.is_empty() {
+            Vec::new()
+        } else {
+            vec![self.clique_frame(Vec::new(), candidates, excluded)]
+        };
+        MaximalCliques { graph: self, stack }
+    }
+
+    fn clique_frame(&self, clique: Vec<usize>, candidates: BTreeSet<usize>, excluded: BTreeSet<usize>) -> Frame {
+        // branching on the pivot's neighbors only repeats cliques found through the pivot
+        let pivot = candidates
+            .iter()
+            .chain(&excluded)
+            .copied()
+            .max_by_key(|&u| candidates.iter().filter(|&&v| self.has_edge(u, v)).count());
+        let todo = match pivot {
+            Some(pivot) => candidates.iter().copied().filter(|&v| !self.has_edge(pivot, v)).collect(),
+            None => Vec::new(),
+        };
+        Frame { clique, candidates, excluded, todo }
+    }
+
+    /// Nodes in the order of repeatedly removing a node of minimum remaining degree
+    pub fn degeneracy_order(&self) -> Vec<usize> {
+        let mut degrees: Vec<usize> = self.nodes().map(|id| self.degree(id)).collect();
+        let mut queue: BTreeSet<(usize, usize)> = self.nodes().map(|id| (degrees[id], id)).collect();
+        let mut removed = vec![false; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+        while let Some((_, id)) = queue.pop_first() {
+            removed[id] = true;
+            order.push(id);
+            for next in self.neighbors(id).filter(|&next| !removed[next]) {
+                queue.remove(&(degrees[next], next));
+                degrees[next] -= 1;
+                queue.insert((degrees[next], next));
+            }
+        }
+        order
+    }
+
+    /// A largest clique, searching from each node in degeneracy order
+    /// among its neighbors that come later in that order
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        let order = self.degeneracy_order();
+        let mut position = vec![0; self.len()];
+        for (i, &id) in order.iter().enumerate() {
+            position[id] = i;
+        }
+
+        let mut best = Vec::new();
+        for &v in &order {
+            let later: Vec<usize> = self.neighbors(v).filter(|&u| position[u] > position[v]).collect();
+            if later.len() < best.len() {
+                continue;
+            }
+            self.grow_maximum_clique(&mut vec![v], &later, &mut best);
+        }
+        best.sort_unstable();
+        best
+    }
+
+    fn grow_maximum_clique(&self, clique: &mut Vec<usize>, candidates: &[usize], best: &mut Vec<usize>) {
+        if candidates.is_empty() {
+            if clique.len() > best.len() {
+                *best = clique.clone();
+            }
+            return;
+        }
+        for (i, &v) in candidates.iter().enumerate() {
+            if clique.len() + candidates.len() - i <= best.len() {
+                return;
+            }
+            let next: Vec<usize> = candidates[i + 1..].iter().copied().filter(|&u| self.has_edge(v, u)).collect();
+            clique.push(v);
+            self.grow_maximum_clique(clique, &next, best);
+            clique.pop();
+        }
+    }
+
+    /// Sorted names of the clique joined by commas
+    pub fn clique_password(&self, clique: &[usize]) -> String
+    where
+        N: Ord + Display,
+    {
+        let mut names: Vec<&N> = clique.iter().map(|&id| self.name(id)).collect();
+        names.sort();
+        names.iter().map(|name| name.to_string()).collect::<Vec<_>>().join(",")
+    }
+}
+
+/// One level of the Bron–Kerbosch recursion
+struct Frame {
+    clique: Vec<usize>,
+    candidates: BTreeSet<usize>,
+    excluded: BTreeSet<usize>,
+    /// Candidates still to branch on
+    todo: Vec<usize>,
+}
+
+/// Iterator returned by `Graph::maximal_cliques`
+pub struct MaximalCliques<'a, N, W> {
+    graph: &'a Graph<N, W>,
+    stack: Vec<Frame>,
+}
+
+impl<N: Hash + Eq + Clone, W: Clone> Iterator for MaximalCliques<'_, N, W> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let graph = self.graph;
+        while let Some(frame) = self.stack.last_mut() {
+            let Some(v) = frame.todo.pop() else {
+                self.stack.pop();
+                continue;
+            };
+            let mut clique = frame.clique.clone();
+            clique.push(v);
+            let candidates: BTreeSet<usize> = frame.candidates.iter().copied().filter(|&u| graph.has_edge(v, u)).collect();
+            let excluded: BTreeSet<usize> = frame.excluded.iter().copied().filter(|&u| graph.has_edge(v, u)).collect();
+            frame.candidates.remove(&v);
+            frame.excluded.insert(v);
+
+            if candidates.is_empty() {
+                if excluded.is_empty() {
+                    clique.sort_unstable();
+                    return Some(clique);
+                }
+                continue;
+            }
+            let next = graph.clique_frame(clique, candidates, excluded);
+            self.stack.push(next);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // two triangles sharing an edge, plus a pendant node and a separate 4-clique
+    fn example() -> Graph<char> {
+        Graph::from_edges(false, [
+            ('a', 'b'), ('a', 'c'), ('b', 'c'), ('b', 'd'), ('c', 'd'), ('d', 'e'),
+            ('w', 'x'), ('w', 'y'), ('w', 'z'), ('x', 'y'), ('x', 'z'), ('y', 'z'),
+        ])
+    }
+
+    fn names(graph: &Graph<char>, cliques: &[Vec<usize>]) -> Vec<String> {
+        let mut names: Vec<String> = cliques.iter().map(|clique| graph.clique_password(clique)).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_k_cliques() {
+        let graph = example();
+        assert_eq!(names(&graph, &graph.k_cliques(3)), ["a,b,c", "b,c,d", "w,x,y", "w,x,z", "w,y,z", "x,y,z"]);
+        assert_eq!(graph.k_cliques(2).len(), graph.edge_count());
+        assert_eq!(graph.k_cliques(1).len(), graph.len());
+        assert_eq!(names(&graph, &graph.k_cliques(4)), ["w,x,y,z"]);
+        assert!(graph.k_cliques(5).is_empty());
+    }
+
+    #[test]
+    fn test_k_cliques_where() {
+        let graph = example();
+        assert_eq!(names(&graph, &graph.k_cliques_where(3, |&c| c == 'd')), ["b,c,d"]);
+        assert_eq!(graph.k_cliques_where(3, |&c| c > 'v').len(), 4);
+        assert!(graph.k_cliques_where(2, |&c| c == 'q').is_empty());
+    }
+
+    #[test]
+    fn test_maximal_cliques() {
+        let graph = example();
+        let cliques: Vec<_> = graph.maximal_cliques().collect();
+        assert_eq!(names(&graph, &cliques), ["a,b,c", "b,c,d", "d,e", "w,x,y,z"]);
+
+        // the iterator is lazy, so it can stop early
+        assert!(graph.maximal_cliques().any(|clique| clique.len() == 4));
+        assert_eq!(Graph::<char>::undirected().maximal_cliques().count(), 0);
+    }
+
+    #[test]
+    fn test_maximum_clique() {
+        let graph = example();
+        assert_eq!(graph.clique_password(&graph.maximum_clique()), "w,x,y,z");
+
+        let order = graph.degeneracy_order();
+        assert_eq!(order.len(), graph.len());
+        assert_eq!(*graph.name(order[0]), 'e');
+        assert!(Graph::<char>::undirected().maximum_clique().is_empty());
+    }
+
+    #[test]
+    fn test_clique_password() {
+        let graph = Graph::parse("ta-co\nka-de\nco-ka\nde-ta\nco-de\nka-ta", '-', false);
+        let clique = graph.maximum_clique();
+        assert_eq!(graph.clique_password(&clique), "co,de,ka,ta");
+        assert!(graph.clique_contains(&clique, |name| name.starts_with('t')));
+    }
+}
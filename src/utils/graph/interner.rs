@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Assigns dense `usize` ids to names, in order of first appearance
+#[derive(Debug, Clone)]
+pub struct Interner<N> {
+    ids: HashMap<N, usize>,
+    names: Vec<N>,
+}
+
+impl<N> Default for Interner<N> {
+    fn default() -> Self {
+        Self { ids: HashMap::new(), names: Vec::new() }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Interner<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Id of `name`, assigning the next free one if it is new
+    pub fn intern(&mut self, name: N) -> usize {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.ids.insert(name.clone(), id);
+        self.names.push(name);
+        id
+    }
+
+    pub fn id(&self, name: &N) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// Name of an id, panics if it was never assigned
+    pub fn name(&self, id: usize) -> &N {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Names in id order
+    pub fn names(&self) -> &[N] {
+        &self.names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut names = Interner::new();
+        assert_eq!(names.intern("kh"), 0);
+        assert_eq!(names.intern("tc"), 1);
+        assert_eq!(names.intern("kh"), 0);
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.id(&"tc"), Some(1));
+        assert_eq!(names.id(&"qp"), None);
+        assert_eq!(*names.name(1), "tc");
+        assert_eq!(names.names(), &["kh", "tc"]);
+    }
+}
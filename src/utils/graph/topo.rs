@@ -0,0 +1,154 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+use super::Graph;
+
+/// Nodes of a directed cycle that prevents a topological sort, in edge order starting from the smallest id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub nodes: Vec<usize>,
+}
+
+/// Precedence queries where an edge `a -> b` means `a` must come before `b`
+impl<N: Hash + Eq + Clone, W: Clone> Graph<N, W> {
+    /// Every node ordered so that all edges point forward
+    pub fn topological_sort(&self) -> Result<Vec<usize>, Cycle> {
+        self.topological_sort_subset(&self.nodes().collect::<Vec<_>>())
+    }
+
+    /// Order the given distinct nodes using only the edges between them.
+    /// Nodes that are not constrained relative to each other keep their given order.
+    pub fn topological_sort_subset(&self, nodes: &[usize]) -> Result<Vec<usize>, Cycle> {
+        let position: HashMap<usize, usize> = nodes.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let successors = |i: usize| self.neighbors(nodes[i]).filter_map(|next| position.get(&next).copied());
+
+        let mut in_degree = vec![0; nodes.len()];
+        for i in 0..nodes.len() {
+            successors(i).for_each(|j| in_degree[j] += 1);
+        }
+
+        // Kahn's algorithm, always taking the earliest ready node
+        let mut ready: BTreeSet<usize> = (0..nodes.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(nodes.len());
+        while let Some(i) = ready.pop_first() {
+            order.push(nodes[i]);
+            for j in successors(i) {
+                in_degree[j] -= 1;
+                if in_degree[j] == 0 {
+                    ready.insert(j);
+                }
+            }
+        }
+
+        if order.len() == nodes.len() {
+            return Ok(order);
+        }
+        // every node left over still has a predecessor that is left over,
+        // so walking backwards through them must run into a cycle
+        let blocked: Vec<usize> = (0..nodes.len()).filter(|&i| in_degree[i] > 0).map(|i| nodes[i]).collect();
+        let mut walk = vec![blocked[0]];
+        let mut seen = HashMap::from([(blocked[0], 0)]);
+        loop {
+            let current = *walk.last().unwrap();
+            let previous = *blocked.iter().find(|&&p| self.has_edge(p, current)).unwrap();
+            if let Some(&start) = seen.get(&previous) {
+                let mut nodes = walk.split_off(start);
+                nodes.reverse();
+                let smallest = (0..nodes.len()).min_by_key(|&i| nodes[i]).unwrap();
+                nodes.rotate_left(smallest);
+                return Err(Cycle { nodes });
+            }
+            seen.insert(previous, walk.len());
+            walk.push(previous);
+        }
+    }
+
+    /// Edges `(a, b)` broken by `sequence`, i.e. where `b` doesn't come after `a`.
+    /// A self-loop is always broken; edges to nodes missing from the sequence are ignored.
+    pub fn violations(&self, sequence: &[usize]) -> Vec<(usize, usize)> {
+        let position: HashMap<usize, usize> = sequence.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        sequence
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &a)| {
+                self.neighbors(a)
+                    .filter(|b| position.get(b).is_some_and(|&j| j <= i))
+                    .map(move |b| (a, b))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Whether `sequence` breaks none of the edges between its nodes
+    pub fn satisfies(&self, sequence: &[usize]) -> bool {
+        self.violations(sequence).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(graph: &Graph<char>, names: &str) -> Vec<usize> {
+        names.chars().map(|c| graph.id(&c).unwrap()).collect()
+    }
+
+    fn names(graph: &Graph<char>, ids: &[usize]) -> String {
+        ids.iter().map(|&id| *graph.name(id)).collect()
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = Graph::from_edges(true, [('c', 'a'), ('b', 'a'), ('c', 'b'), ('d', 'e')]);
+        let order = graph.topological_sort().unwrap();
+        assert_eq!(names(&graph, &order), "cbade");
+        assert!(graph.satisfies(&order));
+
+        // unconstrained nodes keep their given order
+        assert_eq!(names(&graph, &graph.topological_sort_subset(&ids(&graph, "edab")).unwrap()), "deba");
+    }
+
+    #[test]
+    fn test_subset_of_cyclic_rules() {
+        // rock-paper-scissors: every pair is ordered, but the whole set is cyclic
+        let graph = Graph::from_edges(true, [('r', 's'), ('s', 'p'), ('p', 'r')]);
+        let subset = ids(&graph, "sr");
+        assert_eq!(names(&graph, &graph.topological_sort_subset(&subset).unwrap()), "rs");
+
+        let cycle = graph.topological_sort().unwrap_err();
+        assert_eq!(cycle.nodes.len(), 3);
+        for (i, &a) in cycle.nodes.iter().enumerate() {
+            assert!(graph.has_edge(a, cycle.nodes[(i + 1) % 3]));
+        }
+    }
+
+    #[test]
+    fn test_cycle_behind_other_nodes() {
+        let graph = Graph::from_edges(true, [('a', 'b'), ('b', 'c'), ('c', 'd'), ('d', 'b'), ('x', 'x')]);
+        let cycle = graph.topological_sort_subset(&ids(&graph, "abcd")).unwrap_err();
+        assert_eq!(names(&graph, &cycle.nodes), "bcd");
+        assert_eq!(graph.topological_sort_subset(&ids(&graph, "x")).unwrap_err().nodes, ids(&graph, "x"));
+    }
+
+    #[test]
+    fn test_violations() {
+        let graph = Graph::from_edges(true, [('a', 'b'), ('b', 'c'), ('a', 'c'), ('c', 'z')]);
+        assert!(graph.violations(&ids(&graph, "abc")).is_empty());
+        assert!(graph.satisfies(&ids(&graph, "ac")));
+
+        let broken = graph.violations(&ids(&graph, "cab"));
+        let broken: Vec<String> = broken.iter().map(|&(a, b)| names(&graph, &[a, b])).collect();
+        assert_eq!(broken, ["ac", "bc"]);
+        assert!(!graph.satisfies(&ids(&graph, "cab")));
+    }
+
+    #[test]
+    fn test_self_loop() {
+        let graph = Graph::from_edges(true, [('a', 'b'), ('b', 'b')]);
+        let sequence = ids(&graph, "ab");
+        let broken: Vec<String> = graph.violations(&sequence).iter().map(|&(a, b)| names(&graph, &[a, b])).collect();
+        assert_eq!(broken, ["bb"]);
+        assert!(!graph.satisfies(&sequence));
+        assert!(graph.satisfies(&ids(&graph, "a")));
+    }
+}
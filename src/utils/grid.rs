@@ -1,6 +1,20 @@
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
 use std::hash::Hash;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 
+mod region;
+mod search;
+mod sparse;
+mod torus;
+mod transform;
+
+pub use region::{Region, Regions};
+pub use search::{Orientation, PatternMatch, WordMatch};
+pub use sparse::SparseGrid;
+pub use torus::Torus;
+pub use transform::GridView;
+
+/// Grid coordinate: `x` is the column (growing to the right), `y` is the row (growing downwards)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Point {
     pub x: i32,
@@ -12,43 +26,354 @@ impl Point {
         Self { x, y }
     }
 
-    pub fn neighbors(&self) -> Vec<Point> {
-        CARDINAL_DIRECTIONS.iter()
-            .map(|(dx, dy)| Point::new(self.x + dx, self.y + dy))
-            .collect()
+    /// Create a point from `(row, column)` indices as used by `Vec<Vec<T>>` grids
+    pub fn from_row_col(row: usize, col: usize) -> Self {
+        Self::new(col as i32, row as i32)
+    }
+
+    /// `(row, column)` indices of the point, or `None` for negative coordinates
+    pub fn to_row_col(self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+    }
+
+    /// Cardinal neighbours of the point
+    pub fn neighbors(&self) -> impl Iterator<Item = Point> {
+        self.neighbors_with_offsets(CARDINAL_DIRECTIONS)
+    }
+
+    /// Cardinal and diagonal neighbours of the point
+    pub fn neighbors8(&self) -> impl Iterator<Item = Point> {
+        self.neighbors_with_offsets(DIRECTIONS)
     }
 
-    pub fn neighbors_with_bounds(&self, max_x: i32, max_y: i32) -> Vec<Point> {
+    /// Cardinal neighbours with `0 <= x < max_x` (width) and `0 <= y < max_y` (height)
+    pub fn neighbors_with_bounds(&self, max_x: i32, max_y: i32) -> impl Iterator<Item = Point> {
         self.neighbors()
-            .into_iter()
-            .filter(|p| p.x >= 0 && p.x < max_x && p.y >= 0 && p.y < max_y)
-            .collect()
+            .filter(move |p| p.x >= 0 && p.x < max_x && p.y >= 0 && p.y < max_y)
+    }
+
+    /// Points reached by applying each offset to this point
+    pub fn neighbors_with_offsets<V: Into<Vector>>(
+        &self,
+        offsets: impl IntoIterator<Item = V>,
+    ) -> impl Iterator<Item = Point> {
+        let p = *self;
+        offsets.into_iter().map(move |offset| p + offset.into())
+    }
+
+    pub fn manhattan_distance(&self, other: &Point) -> i32 {
+        (*self - *other).manhattan()
+    }
+
+    pub fn chebyshev_distance(&self, other: &Point) -> i32 {
+        (*self - *other).chebyshev()
+    }
+}
+
+/// Displacement between two points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vector {
+    pub dx: i32,
+    pub dy: i32,
+}
+
+impl Vector {
+    pub fn new(dx: i32, dy: i32) -> Self {
+        Self { dx, dy }
+    }
+
+    pub fn manhattan(&self) -> i32 {
+        self.dx.abs() + self.dy.abs()
+    }
+
+    pub fn chebyshev(&self) -> i32 {
+        self.dx.abs().max(self.dy.abs())
+    }
+}
+
+impl From<(i32, i32)> for Vector {
+    fn from((dx, dy): (i32, i32)) -> Self {
+        Self::new(dx, dy)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Point {
+        Point::new(self.x + v.dx, self.y + v.dy)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, v: Vector) -> Point {
+        Point::new(self.x - v.dx, self.y - v.dy)
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, v: Vector) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, k: i32) -> Point {
+        Point::new(self.x * k, self.y * k)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.dx + other.dx, self.dy + other.dy)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.dx - other.dx, self.dy - other.dy)
+    }
+}
+
+impl Mul<i32> for Vector {
+    type Output = Vector;
+
+    fn mul(self, k: i32) -> Vector {
+        Vector::new(self.dx * k, self.dy * k)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.dx, -self.dy)
     }
 }
 
-/// Common 8-directional movement vectors for grid traversal
+/// One of the four cardinal directions, with `North` pointing towards row 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// All directions in clockwise order starting from `North`
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    /// Parse `^>v<` arrows or `NESW` letters
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' | 'N' => Some(Direction::North),
+            '>' | 'E' => Some(Direction::East),
+            'v' | 'S' => Some(Direction::South),
+            '<' | 'W' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    /// Arrow character for the direction
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Unit vector of a single step in this direction
+    pub fn vector(self) -> Vector {
+        match self {
+            Direction::North => Vector::new(0, -1),
+            Direction::East => Vector::new(1, 0),
+            Direction::South => Vector::new(0, 1),
+            Direction::West => Vector::new(-1, 0),
+        }
+    }
+}
+
+/// One of the eight compass directions, including diagonals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// All directions in clockwise order starting from `North`
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    /// Parse `^>v<` arrows or `NESW` letters into the matching cardinal direction
+    pub fn from_char(c: char) -> Option<Self> {
+        Direction::from_char(c).map(Self::from)
+    }
+
+    /// Rotate 45° clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Rotate 45° counter-clockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// Unit vector of a single step in this direction
+    pub fn vector(self) -> Vector {
+        match self {
+            Direction8::North => Vector::new(0, -1),
+            Direction8::NorthEast => Vector::new(1, -1),
+            Direction8::East => Vector::new(1, 0),
+            Direction8::SouthEast => Vector::new(1, 1),
+            Direction8::South => Vector::new(0, 1),
+            Direction8::SouthWest => Vector::new(-1, 1),
+            Direction8::West => Vector::new(-1, 0),
+            Direction8::NorthWest => Vector::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(d: Direction) -> Self {
+        Self::ALL[d as usize * 2]
+    }
+}
+
+/// Inclusive axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    /// Bounding box containing a single point
+    pub fn from_point(p: Point) -> Self {
+        Self { min: p, max: p }
+    }
+
+    /// Smallest bounding box containing all points, or `None` if there are none
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::from_point(points.next()?);
+        points.for_each(|p| bounds.extend(p));
+        Some(bounds)
+    }
+
+    /// Grow the box so that it contains `p`
+    pub fn extend(&mut self, p: Point) {
+        self.min = Point::new(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = Point::new(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// Check if `p` lies on the border of the box
+    pub fn on_edge(&self, p: Point) -> bool {
+        self.contains(p) && (p.x == self.min.x || p.x == self.max.x || p.y == self.min.y || p.y == self.max.y)
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    /// All points inside the box, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let Bounds { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+/// Common 8-directional movement vectors `(dx, dy)` for grid traversal
 pub const DIRECTIONS: [(i32, i32); 8] = [
-    (0, 1),   // right
-    (1, 0),   // down
+    (0, 1),   // down
+    (1, 0),   // right
     (1, 1),   // down-right
-    (1, -1),  // down-left
-    (0, -1),  // left
-    (-1, 0),  // up
+    (1, -1),  // up-right
+    (0, -1),  // up
+    (-1, 0),  // left
     (-1, -1), // up-left
-    (-1, 1),  // up-right
+    (-1, 1),  // down-left
 ];
 
-/// Common 4-directional movement vectors (cardinal directions)
+/// Common 4-directional movement vectors `(dx, dy)` (cardinal directions)
 pub const CARDINAL_DIRECTIONS: [(i32, i32); 4] = [
-    (0, 1),   // right
-    (1, 0),   // down
-    (0, -1),  // left
-    (-1, 0),  // up
+    (0, 1),   // down
+    (1, 0),   // right
+    (0, -1),  // up
+    (-1, 0),  // left
 ];
 
-/// Check if coordinates are within grid bounds
+/// Check if a point is within the bounds of a row-major `Vec<Vec<T>>` grid
 pub fn is_in_bounds<T>(x: i32, y: i32, grid: &[Vec<T>]) -> bool {
-    x >= 0 && y >= 0 && (x as usize) < grid.len() && (y as usize) < grid[0].len()
+    y >= 0 && (y as usize) < grid.len() && x >= 0 && (x as usize) < grid[y as usize].len()
 }
 
 /// Parse a grid from string input where each line represents a row
@@ -61,10 +386,10 @@ pub fn parse_grid(input: &str) -> Vec<Vec<char>> {
 /// Find positions of specific elements in a grid
 pub fn find_positions<T: PartialEq>(grid: &[Vec<T>], target: &T) -> Vec<Point> {
     let mut positions = Vec::new();
-    for (x, row) in grid.iter().enumerate() {
-        for (y, cell) in row.iter().enumerate() {
+    for (row, cells) in grid.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
             if cell == target {
-                positions.push(Point::new(x as i32, y as i32));
+                positions.push(Point::from_row_col(row, col));
             }
         }
     }
@@ -77,3 +402,340 @@ pub fn print_grid<T: Debug>(grid: &[Vec<T>]) {
         println!("{:?}", row);
     }
 }
+
+/// Dense 2D grid stored row-major in a single `Vec`, indexed by `Point { x: column, y: row }`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid of the given size with every cell set to `fill`
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self { cells: vec![fill; width * height], width, height }
+    }
+
+    /// Create a grid from row-major cells
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "cell count does not match grid size");
+        Self { cells, width, height }
+    }
+
+    /// Create a grid from a list of equally long rows
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == width), "rows have different lengths");
+        Self { cells: rows.into_iter().flatten().collect(), width, height }
+    }
+
+    /// Parse a grid from text, mapping every character to a cell
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let rows = input.lines()
+            .map(|line| line.chars().map(&mut f).collect())
+            .collect();
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Check if a point lies inside the grid
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    fn index_of(&self, p: Point) -> Option<usize> {
+        self.contains(p).then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    fn point_of(&self, index: usize) -> Point {
+        Point::new((index % self.width) as i32, (index / self.width) as i32)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index_of(p).map(move |i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Iterate over all cells together with their positions, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().enumerate().map(move |(i, cell)| (self.point_of(i), cell))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> {
+        let width = self.width;
+        self.cells.iter_mut().enumerate()
+            .map(move |(i, cell)| (Point::new((i % width) as i32, (i / width) as i32), cell))
+    }
+
+    /// All positions of the grid, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| Point::new((i % width) as i32, (i / width) as i32))
+    }
+
+    /// Cardinal neighbours of a point that lie inside the grid
+    pub fn neighbors(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbors().filter(move |&n| self.contains(n))
+    }
+
+    /// Position of the first cell matching the predicate
+    pub fn find(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells.iter().position(predicate).map(|i| self.point_of(i))
+    }
+
+    /// Apply a function to every cell, keeping the layout
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(f).collect(), width: self.width, height: self.height }
+    }
+}
+
+impl Grid<char> {
+    /// Parse a grid of characters
+    pub fn parse_chars(input: &str) -> Self {
+        Self::parse(input, |c| c)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p).unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+/// Renders the grid back to text, one line per row
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.##
+#..S
+E..#";
+
+    #[test]
+    fn test_grid_parse_and_display() {
+        let grid = Grid::parse_chars(EXAMPLE);
+        assert_eq!(grid.width(), 4);
+        assert_eq!(grid.height(), 3);
+        assert_eq!(grid.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn test_grid_access() {
+        let mut grid = Grid::parse_chars(EXAMPLE);
+        assert_eq!(grid.get(Point::new(3, 1)), Some(&'S'));
+        assert_eq!(grid.get(Point::new(4, 1)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.find(|&c| c == 'E'), Some(Point::new(0, 2)));
+
+        grid[Point::new(1, 0)] = '#';
+        assert_eq!(grid.row(0), &['#', '#', '#', '#']);
+        assert_eq!(grid.column(3).collect::<String>(), "#S#");
+        assert_eq!(grid.columns().count(), 4);
+    }
+
+    #[test]
+    fn test_grid_iteration() {
+        let grid = Grid::parse(EXAMPLE, |c| c == '#');
+        let walls: Vec<_> = grid.iter().filter(|(_, &wall)| wall).map(|(p, _)| p).collect();
+        assert_eq!(walls[..3], [Point::new(0, 0), Point::new(2, 0), Point::new(3, 0)]);
+        assert_eq!(grid.positions().last(), Some(Point::new(3, 2)));
+        assert_eq!(grid.map(|&wall| if wall { 'x' } else { ' ' }).row(2), &[' ', ' ', ' ', 'x']);
+    }
+
+    #[test]
+    fn test_row_col_conversion() {
+        let p = Point::from_row_col(2, 5);
+        assert_eq!(p, Point::new(5, 2));
+        assert_eq!(p.to_row_col(), Some((2, 5)));
+        assert_eq!(Point::new(-1, 0).to_row_col(), None);
+    }
+
+    #[test]
+    fn test_helpers_agree_with_grid() {
+        let rows = parse_grid(EXAMPLE);
+        let grid = Grid::parse_chars(EXAMPLE);
+
+        let s = find_positions(&rows, &'S');
+        assert_eq!(s, vec![Point::new(3, 1)]);
+        assert_eq!(grid.get(s[0]), Some(&'S'));
+        assert_eq!(grid.find(|&c| c == 'S'), Some(s[0]));
+
+        assert!(is_in_bounds(3, 2, &rows));
+        assert!(!is_in_bounds(2, 3, &rows));
+        assert!(!is_in_bounds(-1, 0, &rows));
+        for p in grid.positions() {
+            assert!(is_in_bounds(p.x, p.y, &rows));
+        }
+    }
+
+    #[test]
+    fn test_neighbors_with_bounds() {
+        let grid = Grid::parse_chars(EXAMPLE);
+        let width = grid.width() as i32;
+        let height = grid.height() as i32;
+
+        let mut corner: Vec<_> = Point::new(3, 2).neighbors_with_bounds(width, height).collect();
+        corner.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(corner, vec![Point::new(3, 1), Point::new(2, 2)]);
+
+        for p in grid.positions() {
+            assert!(p.neighbors_with_bounds(width, height).all(|n| grid.contains(n)));
+            assert!(p.neighbors_with_bounds(width, height).eq(grid.neighbors(p)));
+        }
+    }
+
+    #[test]
+    fn test_neighbor_iterators() {
+        let p = Point::new(0, 0);
+        assert_eq!(p.neighbors().count(), 4);
+        assert_eq!(p.neighbors8().count(), 8);
+        assert!(p.neighbors8().all(|n| n != p && n.chebyshev_distance(&p) == 1));
+
+        let knight = [(1, 2), (2, 1), (-1, 2), (-2, 1)];
+        let jumps: Vec<_> = p.neighbors_with_offsets(knight).collect();
+        assert_eq!(jumps[1], Point::new(2, 1));
+        let steps: Vec<_> = p.neighbors_with_offsets(Direction::ALL.map(Direction::vector)).collect();
+        assert_eq!(steps, vec![Point::new(0, -1), Point::new(1, 0), Point::new(0, 1), Point::new(-1, 0)]);
+    }
+
+    #[test]
+    fn test_direction_vectors() {
+        let rows = parse_grid(EXAMPLE);
+        let (dx, dy) = CARDINAL_DIRECTIONS[1];
+        let s = find_positions(&rows, &'S')[0];
+        // `(1, 0)` steps one column to the right within the same row
+        let e = find_positions(&rows, &'E')[0];
+        assert_eq!((e.x + dx, e.y + dy), (1, 2));
+        assert!(!is_in_bounds(s.x + dx, s.y + dy, &rows));
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(Bounds::from_points(std::iter::empty()), None);
+
+        let bounds = Bounds::from_points([Point::new(2, -1), Point::new(-3, 4), Point::new(0, 0)]).unwrap();
+        assert_eq!(bounds.min, Point::new(-3, -1));
+        assert_eq!(bounds.max, Point::new(2, 4));
+        assert_eq!((bounds.width(), bounds.height()), (6, 6));
+        assert!(bounds.contains(Point::new(0, 4)));
+        assert!(!bounds.contains(Point::new(3, 0)));
+        assert!(bounds.on_edge(Point::new(-3, 2)));
+        assert!(!bounds.on_edge(Point::new(0, 0)));
+        assert_eq!(bounds.positions().count(), 36);
+        assert_eq!(bounds.positions().next(), Some(bounds.min));
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let p = Point::new(2, 3);
+        let v = Vector::new(-1, 4);
+        assert_eq!(p + v, Point::new(1, 7));
+        assert_eq!(p - v, Point::new(3, -1));
+        assert_eq!((p + v) - p, v);
+        assert_eq!(p * 3, Point::new(6, 9));
+        assert_eq!(v * -2, Vector::new(2, -8));
+        assert_eq!(-v + v, Vector::default());
+
+        let mut q = p;
+        q += Direction::East.vector() * 5;
+        assert_eq!(q, Point::new(7, 3));
+        q -= Direction::North.vector();
+        assert_eq!(q, Point::new(7, 4));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, -2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a.manhattan_distance(&b), 11);
+        assert_eq!(a.chebyshev_distance(&b), 7);
+        assert_eq!(b.manhattan_distance(&a), 11);
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::from_char('^'), Some(Direction::North));
+        assert_eq!(Direction::from_char('W'), Some(Direction::West));
+        assert_eq!(Direction::from_char('x'), None);
+        assert_eq!(Direction::South.to_char(), 'v');
+
+        for d in Direction::ALL {
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.turn_right().turn_right(), d.opposite());
+            assert_eq!(d.opposite().vector(), -d.vector());
+            assert_eq!(Direction::from_char(d.to_char()), Some(d));
+        }
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Point::new(0, 0) + Direction::North.vector(), Point::new(0, -1));
+    }
+
+    #[test]
+    fn test_direction8() {
+        assert_eq!(Direction8::North.turn_right(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::from_char('>'), Some(Direction8::East));
+        assert_eq!(Direction8::from(Direction::West), Direction8::West);
+
+        for d in Direction8::ALL {
+            assert_eq!(d.opposite().opposite(), d);
+            assert_eq!(d.opposite().vector(), -d.vector());
+            assert_eq!(d.vector().chebyshev(), 1);
+            assert_eq!(d.vector().manhattan(), if d.is_diagonal() { 2 } else { 1 });
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use super::{Bounds, Direction, Grid, Point};
+
+/// 4-connected group of cells with its shape metrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub label: usize,
+    pub cells: Vec<Point>,
+    /// Number of cell edges bordering another region or the outside of the grid
+    pub perimeter: usize,
+    /// Number of straight fence sides, which equals the number of corners
+    pub sides: usize,
+    pub bounds: Bounds,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// Result of labelling a grid: the label of every cell plus the regions, indexed by label
+#[derive(Debug, Clone)]
+pub struct Regions {
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl<T> Grid<T> {
+    /// Split the grid into 4-connected regions, where two adjacent cells belong
+    /// to the same region if `same_region` returns true for them
+    pub fn regions(&self, same_region: impl Fn(&T, &T) -> bool) -> Regions {
+        let mut labels = Grid::new(self.width(), self.height(), usize::MAX);
+        let mut regions = Vec::new();
+
+        for start in self.positions() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+            let label = regions.len();
+            labels[start] = label;
+            let mut cells = vec![start];
+            let mut stack = vec![start];
+            while let Some(pos) = stack.pop() {
+                for next in self.neighbors(pos) {
+                    if labels[next] == usize::MAX && same_region(&self[pos], &self[next]) {
+                        labels[next] = label;
+                        cells.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            regions.push(Region {
+                label,
+                bounds: Bounds::from_points(cells.iter().copied()).unwrap(),
+                cells,
+                perimeter: 0,
+                sides: 0,
+            });
+        }
+
+        for region in &mut regions {
+            let inside = |p: Point| labels.get(p) == Some(&region.label);
+            for &cell in &region.cells {
+                for dir in Direction::ALL {
+                    let side = !inside(cell + dir.vector());
+                    if side {
+                        region.perimeter += 1;
+                    }
+                    let next = dir.turn_right();
+                    let other_side = !inside(cell + next.vector());
+                    let diagonal = inside(cell + dir.vector() + next.vector());
+                    // convex corner, or concave corner where the diagonal cell is missing
+                    if (side && other_side) || (!side && !other_side && !diagonal) {
+                        region.sides += 1;
+                    }
+                }
+            }
+        }
+
+        Regions { labels, regions }
+    }
+
+    /// Regions of equal adjacent cells
+    pub fn regions_by_value(&self) -> Regions
+    where
+        T: PartialEq,
+    {
+        self.regions(|a, b| a == b)
+    }
+}
+
+impl Regions {
+    pub fn region_at(&self, p: Point) -> Option<&Region> {
+        self.labels.get(p).map(|&label| &self.regions[label])
+    }
+
+    /// Label of the region that separates `label` from the edge of the grid, if there is one.
+    /// Regions touching the edge of the grid are never enclosed.
+    pub fn enclosed_by(&self, label: usize) -> Option<usize> {
+        let region = &self.regions[label];
+        // the enclosing region has to border this one
+        let mut candidates: Vec<usize> = region
+            .cells
+            .iter()
+            .flat_map(|p| p.neighbors())
+            .filter_map(|p| self.labels.get(p).copied())
+            .filter(|&l| l != label)
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates.into_iter().find(|&outer| self.separates(outer, region))
+    }
+
+    /// Whether every path from `region` to the edge of the grid crosses region `outer`
+    fn separates(&self, outer: usize, region: &Region) -> bool {
+        let mut seen = Grid::new(self.labels.width(), self.labels.height(), false);
+        let mut stack = region.cells.clone();
+        stack.iter().for_each(|&p| seen[p] = true);
+        while let Some(pos) = stack.pop() {
+            for next in pos.neighbors() {
+                match self.labels.get(next) {
+                    None => return false,
+                    Some(&l) if l == outer || seen[next] => {}
+                    Some(_) => {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_metrics() {
+        let grid = Grid::parse_chars("AAAA\nBBCD\nBBCC\nEEEC");
+        let regions = grid.regions_by_value();
+        assert_eq!(regions.regions.len(), 5);
+
+        let metrics: Vec<_> = regions.regions.iter()
+            .map(|r| (grid[r.cells[0]], r.area(), r.perimeter, r.sides))
+            .collect();
+        assert_eq!(metrics, vec![
+            ('A', 4, 10, 4),
+            ('B', 4, 8, 4),
+            ('C', 4, 10, 8),
+            ('D', 1, 4, 4),
+            ('E', 3, 8, 4),
+        ]);
+
+        let c = regions.region_at(Point::new(2, 1)).unwrap();
+        assert_eq!((c.bounds.min, c.bounds.max), (Point::new(2, 1), Point::new(3, 3)));
+        assert_eq!(regions.labels[Point::new(3, 3)], c.label);
+    }
+
+    #[test]
+    fn test_holes_and_enclosure() {
+        let grid = Grid::parse_chars("\
+OOOOO
+OXOXO
+OOOOO
+OXOXO
+OOOOO");
+        let regions = grid.regions_by_value();
+        let outer = regions.region_at(Point::new(0, 0)).unwrap();
+        assert_eq!((outer.area(), outer.perimeter, outer.sides), (21, 36, 20));
+
+        let hole = regions.region_at(Point::new(1, 1)).unwrap();
+        assert_eq!(regions.enclosed_by(hole.label), Some(outer.label));
+        assert_eq!(regions.enclosed_by(outer.label), None);
+    }
+
+    #[test]
+    fn test_nested_enclosure() {
+        let grid = Grid::parse_chars("\
+aaaaaaa
+abbbbba
+abcccba
+abcdcba
+abcccba
+abbbbba
+aaaaaaa");
+        let regions = grid.regions_by_value();
+        let label = |p| regions.labels[p];
+        assert_eq!(regions.enclosed_by(label(Point::new(3, 3))), Some(label(Point::new(2, 2))));
+        assert_eq!(regions.enclosed_by(label(Point::new(2, 2))), Some(label(Point::new(1, 1))));
+        assert_eq!(regions.enclosed_by(label(Point::new(1, 1))), Some(label(Point::new(0, 0))));
+        assert_eq!(regions.enclosed_by(label(Point::new(0, 0))), None);
+
+        // neighbouring regions can share the same enclosure
+        let grid = Grid::parse_chars("aaaa\nabca\naaaa");
+        let regions = grid.regions_by_value();
+        assert_eq!(regions.enclosed_by(regions.labels[Point::new(1, 1)]), Some(0));
+        assert_eq!(regions.enclosed_by(regions.labels[Point::new(2, 1)]), Some(0));
+
+        // an opening to the edge of the grid breaks the enclosure
+        let grid = Grid::parse_chars("aaaa\nabca\naaca");
+        let regions = grid.regions_by_value();
+        assert_eq!(regions.enclosed_by(regions.labels[Point::new(1, 1)]), None);
+
+        // a region right below another enclosed region
+        let grid = Grid::parse_chars("aaaaa\naabaa\naaxaa\naaaaa");
+        let regions = grid.regions_by_value();
+        assert_eq!(regions.enclosed_by(regions.labels[Point::new(2, 1)]), Some(0));
+        assert_eq!(regions.enclosed_by(regions.labels[Point::new(2, 2)]), Some(0));
+    }
+
+    #[test]
+    fn test_custom_predicate() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![9, 9, 4]]);
+        let regions = grid.regions(|a: &i32, b: &i32| (a - b).abs() == 1);
+        // the two 9s differ by 0, so each is a region on its own
+        assert_eq!(regions.regions.len(), 3);
+        assert_eq!(regions.region_at(Point::new(2, 1)).unwrap().area(), 4);
+    }
+}
@@ -0,0 +1,141 @@
+use super::{Direction8, Grid, Point, Vector};
+
+/// Occurrence of a word: its first letter and the direction it reads in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    pub start: Point,
+    pub direction: Direction8,
+}
+
+/// How a pattern was transformed before matching, in the order of `Grid::symmetries`:
+/// `rotation` quarter turns clockwise, then a left-right mirror if `flipped`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub rotation: u8,
+    pub flipped: bool,
+}
+
+/// Occurrence of a 2D pattern: the top-left corner of the matching window and the pattern's orientation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    pub origin: Point,
+    pub orientation: Orientation,
+}
+
+impl Grid<Option<char>> {
+    /// Parse a search pattern where `wildcard` matches any cell
+    pub fn parse_pattern(input: &str, wildcard: char) -> Self {
+        Grid::parse(input, |c| (c != wildcard).then_some(c))
+    }
+}
+
+impl Grid<char> {
+    /// Every occurrence of `word` along the given directions, overlapping ones included
+    pub fn find_str(&self, word: &str, directions: &[Direction8]) -> Vec<WordMatch> {
+        self.find_word(&word.chars().collect::<Vec<_>>(), directions)
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Every occurrence of `word` along the given directions, overlapping ones included
+    pub fn find_word(&self, word: &[T], directions: &[Direction8]) -> Vec<WordMatch> {
+        let Some(first) = word.first() else {
+            return Vec::new();
+        };
+        self.iter()
+            .filter(|&(_, cell)| cell == first)
+            .flat_map(|(start, _)| {
+                directions.iter()
+                    .filter(move |dir| self.reads_word(start, dir.vector(), word))
+                    .map(move |&direction| WordMatch { start, direction })
+            })
+            .collect()
+    }
+
+    fn reads_word(&self, start: Point, step: Vector, word: &[T]) -> bool {
+        let mut pos = start;
+        for letter in word {
+            if self.get(pos) != Some(letter) {
+                return false;
+            }
+            pos += step;
+        }
+        true
+    }
+
+    /// Top-left corners of every window matching `pattern` as given; `None` cells match anything
+    pub fn find_pattern(&self, pattern: &Grid<Option<T>>) -> Vec<Point> {
+        self.windows(pattern.width(), pattern.height())
+            .filter(|window| {
+                pattern.iter().all(|(offset, expected)| match expected {
+                    Some(expected) => window.get(offset) == Some(expected),
+                    None => true,
+                })
+            })
+            .map(|window| window.origin())
+            .collect()
+    }
+
+    /// Matches of `pattern` under all rotations and reflections.
+    /// Symmetries that produce an identical pattern are only searched once.
+    pub fn find_pattern_symmetries(&self, pattern: &Grid<Option<T>>) -> Vec<PatternMatch>
+    where
+        T: Clone,
+    {
+        let mut seen: Vec<Grid<Option<T>>> = Vec::with_capacity(8);
+        let mut matches = Vec::new();
+        for (i, variant) in pattern.symmetries().into_iter().enumerate() {
+            if seen.contains(&variant) {
+                continue;
+            }
+            let orientation = Orientation { rotation: (i % 4) as u8, flipped: i >= 4 };
+            matches.extend(
+                self.find_pattern(&variant)
+                    .into_iter()
+                    .map(|origin| PatternMatch { origin, orientation }),
+            );
+            seen.push(variant);
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_word() {
+        let grid = Grid::parse_chars("abab\nbaba\nabab");
+        let horizontal = grid.find_str("aba", &[Direction8::East]);
+        assert_eq!(horizontal.len(), 3);
+        assert!(horizontal.contains(&WordMatch { start: Point::new(1, 1), direction: Direction8::East }));
+
+        // overlapping and palindromic matches are all reported
+        assert_eq!(grid.find_str("aba", &[Direction8::East, Direction8::West]).len(), 6);
+        assert_eq!(grid.find_str("aaa", &[Direction8::SouthEast]).len(), 1);
+        assert_eq!(grid.find_str("aaa", &Direction8::ALL).len(), 4);
+        assert!(grid.find_str("", &Direction8::ALL).is_empty());
+        assert!(grid.find_str("abababa", &Direction8::ALL).is_empty());
+    }
+
+    #[test]
+    fn test_find_word_generic() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let matches = grid.find_word(&[6, 5, 4], &Direction8::ALL);
+        assert_eq!(matches, vec![WordMatch { start: Point::new(2, 1), direction: Direction8::West }]);
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let grid = Grid::parse_chars("#..#\n.##.\n#..#");
+        let pattern = Grid::parse_pattern("#?\n?#", '?');
+        assert_eq!(pattern.get(Point::new(1, 0)), Some(&None));
+        assert_eq!(grid.find_pattern(&pattern), vec![Point::new(0, 0), Point::new(2, 1)]);
+
+        let matches = grid.find_pattern_symmetries(&pattern);
+        assert_eq!(matches.len(), 4);
+        let anti_diagonal = Orientation { rotation: 1, flipped: false };
+        assert!(matches.contains(&PatternMatch { origin: Point::new(2, 0), orientation: anti_diagonal }));
+    }
+}
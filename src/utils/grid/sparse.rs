@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use super::{Bounds, Grid, Point};
+
+/// Unbounded grid that only stores occupied cells, keyed by `Point`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self { cells: HashMap::new(), bounds: None }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Smallest box containing every occupied cell
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    /// Set a cell, returning its previous value
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.extend(p),
+            None => self.bounds = Some(Bounds::from_point(p)),
+        }
+        self.cells.insert(p, value)
+    }
+
+    /// Clear a cell, shrinking the bounding box if needed
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        let removed = self.cells.remove(&p)?;
+        if self.bounds.is_some_and(|bounds| bounds.on_edge(p)) {
+            self.bounds = Bounds::from_points(self.cells.keys().copied());
+        }
+        Some(removed)
+    }
+
+    /// Iterate over occupied cells in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&p, value)| (p, value))
+    }
+
+    /// Occupied cardinal neighbours of a point
+    pub fn neighbors(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        p.neighbors().filter_map(move |n| self.get(n).map(|value| (n, value)))
+    }
+
+    /// Occupied cardinal and diagonal neighbours of a point
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = (Point, &T)> {
+        p.neighbors8().filter_map(move |n| self.get(n).map(|value| (n, value)))
+    }
+
+    /// Render the bounding box as text, one line per row
+    pub fn render(&self, mut cell_char: impl FnMut(Option<&T>) -> char) -> String {
+        let Some(bounds) = self.bounds else {
+            return String::new();
+        };
+        let mut out = String::with_capacity((bounds.width() + 1) * bounds.height());
+        for y in bounds.min.y..=bounds.max.y {
+            if y > bounds.min.y {
+                out.push('\n');
+            }
+            for x in bounds.min.x..=bounds.max.x {
+                out.push(cell_char(self.get(Point::new(x, y))));
+            }
+        }
+        out
+    }
+
+    /// Dense copy of the bounding box, with `bounds().min` moved to the origin
+    pub fn to_grid(&self, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let Some(bounds) = self.bounds else {
+            return Grid::from_vec(0, 0, Vec::new());
+        };
+        let offset = bounds.min - Point::new(0, 0);
+        let mut grid = Grid::new(bounds.width(), bounds.height(), fill);
+        for (p, value) in self.iter() {
+            grid[p - offset] = value.clone();
+        }
+        grid
+    }
+
+    /// Sparse copy of the cells of a dense grid that match the predicate
+    pub fn from_grid(grid: &Grid<T>, mut keep: impl FnMut(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        grid.iter()
+            .filter(|(_, value)| keep(value))
+            .map(|(p, value)| (p, value.clone()))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (p, value) in iter {
+            grid.insert(p, value);
+        }
+        grid
+    }
+}
+
+impl<T> From<Grid<T>> for SparseGrid<T>
+where
+    T: Clone,
+{
+    fn from(grid: Grid<T>) -> Self {
+        Self::from_grid(&grid, |_| true)
+    }
+}
+
+/// Renders the bounding box with `.` for empty cells
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(bounds) = self.bounds else {
+            return Ok(());
+        };
+        for y in bounds.min.y..=bounds.max.y {
+            if y > bounds.min.y {
+                writeln!(f)?;
+            }
+            for x in bounds.min.x..=bounds.max.x {
+                match self.get(Point::new(x, y)) {
+                    Some(value) => write!(f, "{}", value)?,
+                    None => write!(f, ".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point::new(-2, 5), 'a');
+        grid.insert(Point::new(3, -1), 'b');
+        assert_eq!(grid.insert(Point::new(0, 0), 'c'), None);
+        assert_eq!(grid.insert(Point::new(0, 0), 'd'), Some('c'));
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&'d'));
+        assert_eq!(grid.get(Point::new(1, 1)), None);
+
+        let bounds = grid.bounds().unwrap();
+        assert_eq!((bounds.min, bounds.max), (Point::new(-2, -1), Point::new(3, 5)));
+
+        grid.remove(Point::new(3, -1));
+        let bounds = grid.bounds().unwrap();
+        assert_eq!((bounds.min, bounds.max), (Point::new(-2, 0), Point::new(0, 5)));
+
+        grid.remove(Point::new(-2, 5));
+        grid.remove(Point::new(0, 0));
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid: SparseGrid<u8> = [(Point::new(0, 0), 1), (Point::new(1, 0), 2), (Point::new(1, 1), 3)]
+            .into_iter()
+            .collect();
+        let cardinal: Vec<_> = grid.neighbors(Point::new(0, 0)).map(|(_, &v)| v).collect();
+        assert_eq!(cardinal, vec![2]);
+        assert_eq!(grid.neighbors8(Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors(Point::new(5, 5)).count(), 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let grid: SparseGrid<char> = [(Point::new(-1, -1), '#'), (Point::new(1, 0), '@')]
+            .into_iter()
+            .collect();
+        assert_eq!(grid.to_string(), "#..\n..@");
+        assert_eq!(grid.render(|cell| if cell.is_some() { 'x' } else { ' ' }), "x  \n  x");
+        assert_eq!(SparseGrid::<char>::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_dense_conversion() {
+        let dense = Grid::parse_chars("#..\n.#.\n..#");
+        let sparse = SparseGrid::from_grid(&dense, |&c| c == '#');
+        assert_eq!(sparse.len(), 3);
+        assert!(sparse.contains(Point::new(2, 2)));
+        assert_eq!(sparse.to_grid('.'), dense);
+
+        let mut shifted = SparseGrid::new();
+        shifted.insert(Point::new(10, -4), 'x');
+        shifted.insert(Point::new(11, -3), 'y');
+        assert_eq!(shifted.to_grid('.').to_string(), "x.\n.y");
+
+        assert_eq!(SparseGrid::from(dense.clone()).to_grid(' '), dense);
+    }
+}
@@ -0,0 +1,109 @@
+use super::{Grid, Point, Vector};
+
+/// Wrap-around area of a given size: leaving one edge re-enters from the opposite one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Torus {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Torus {
+    pub fn new(width: i32, height: i32) -> Self {
+        assert!(width > 0 && height > 0, "torus dimensions must be positive");
+        Self { width, height }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (0..self.width).contains(&p.x) && (0..self.height).contains(&p.y)
+    }
+
+    /// Map any point onto its representative inside `0..width` x `0..height`
+    pub fn wrap(&self, p: Point) -> Point {
+        Point::new(p.x.rem_euclid(self.width), p.y.rem_euclid(self.height))
+    }
+
+    /// Take a single step
+    pub fn step(&self, p: Point, v: Vector) -> Point {
+        self.wrap(p + v)
+    }
+
+    /// Position after moving by `v` for `times` steps, without overflowing for large counts
+    pub fn advance(&self, p: Point, v: Vector, times: i64) -> Point {
+        let axis = |pos: i32, d: i32, size: i32| {
+            let size = size as i64;
+            let offset = (d as i64).rem_euclid(size) * times.rem_euclid(size);
+            ((pos as i64 + offset).rem_euclid(size)) as i32
+        };
+        Point::new(axis(p.x, v.dx, self.width), axis(p.y, v.dy, self.height))
+    }
+
+    /// Cardinal neighbours, wrapped around the edges
+    pub fn neighbors(&self, p: Point) -> impl Iterator<Item = Point> {
+        let torus = *self;
+        p.neighbors().map(move |n| torus.wrap(n))
+    }
+
+    /// Cardinal and diagonal neighbours, wrapped around the edges
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> {
+        let torus = *self;
+        p.neighbors8().map(move |n| torus.wrap(n))
+    }
+}
+
+impl<T> Grid<T> {
+    /// Wrap-around view of the grid's dimensions
+    pub fn torus(&self) -> Torus {
+        Torus::new(self.width() as i32, self.height() as i32)
+    }
+
+    /// Cell at `p` with coordinates taken modulo the grid size
+    pub fn get_wrapped(&self, p: Point) -> &T {
+        &self[self.torus().wrap(p)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_and_step() {
+        let torus = Torus::new(11, 7);
+        assert_eq!(torus.wrap(Point::new(-1, 7)), Point::new(10, 0));
+        assert_eq!(torus.wrap(Point::new(-23, -15)), Point::new(10, 6));
+        assert_eq!(torus.step(Point::new(10, 0), Vector::new(3, -2)), Point::new(2, 5));
+        assert!(torus.contains(torus.wrap(Point::new(1000, -1000))));
+    }
+
+    #[test]
+    fn test_advance_matches_repeated_steps() {
+        let torus = Torus::new(11, 7);
+        let start = Point::new(2, 4);
+        let velocity = Vector::new(2, -3);
+
+        let mut p = start;
+        for t in 1..=100 {
+            p = torus.step(p, velocity);
+            assert_eq!(torus.advance(start, velocity, t), p);
+        }
+        assert_eq!(torus.advance(start, velocity, 5), Point::new(1, 3));
+        assert_eq!(torus.advance(start, -velocity, -5), Point::new(1, 3));
+        assert_eq!(torus.advance(start, velocity, 11 * 7 * 1_000_000_000_000), start);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let torus = Torus::new(3, 2);
+        let corner: Vec<_> = torus.neighbors(Point::new(0, 0)).collect();
+        assert_eq!(corner, vec![Point::new(0, 1), Point::new(1, 0), Point::new(0, 1), Point::new(2, 0)]);
+        assert!(torus.neighbors8(Point::new(2, 1)).all(|n| torus.contains(n)));
+    }
+
+    #[test]
+    fn test_grid_wrapping() {
+        let grid = Grid::parse_chars("ab\ncd");
+        assert_eq!(grid.torus(), Torus::new(2, 2));
+        assert_eq!(grid.get_wrapped(Point::new(-1, 3)), &'d');
+        assert_eq!(grid.get_wrapped(Point::new(4, 4)), &'a');
+    }
+}
@@ -0,0 +1,212 @@
+use super::{Grid, Point};
+
+impl<T: Clone> Grid<T> {
+    /// Build a grid of the given size where every cell is copied from `source(dest)`
+    fn remap(&self, width: usize, height: usize, source: impl Fn(i32, i32) -> Point) -> Grid<T> {
+        let cells = (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| (x, y)))
+            .map(|(x, y)| self[source(x, y)].clone())
+            .collect();
+        Grid::from_vec(width, height, cells)
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(&self) -> Grid<T> {
+        self.remap(self.height(), self.width(), |x, y| Point::new(y, x))
+    }
+
+    /// Rotate 90° clockwise
+    pub fn rotate_right(&self) -> Grid<T> {
+        let h = self.height() as i32;
+        self.remap(self.height(), self.width(), |x, y| Point::new(y, h - 1 - x))
+    }
+
+    /// Rotate 90° counter-clockwise
+    pub fn rotate_left(&self) -> Grid<T> {
+        let w = self.width() as i32;
+        self.remap(self.height(), self.width(), |x, y| Point::new(w - 1 - y, x))
+    }
+
+    pub fn rotate_180(&self) -> Grid<T> {
+        let (w, h) = (self.width() as i32, self.height() as i32);
+        self.remap(self.width(), self.height(), |x, y| Point::new(w - 1 - x, h - 1 - y))
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let w = self.width() as i32;
+        self.remap(self.width(), self.height(), |x, y| Point::new(w - 1 - x, y))
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(&self) -> Grid<T> {
+        let h = self.height() as i32;
+        self.remap(self.width(), self.height(), |x, y| Point::new(x, h - 1 - y))
+    }
+
+    /// The 8 rotations and reflections of the grid, starting with the grid itself
+    pub fn symmetries(&self) -> Vec<Grid<T>> {
+        let mut rotations = vec![self.clone()];
+        for _ in 0..3 {
+            let next = rotations.last().unwrap().rotate_right();
+            rotations.push(next);
+        }
+        let flipped: Vec<_> = rotations.iter().map(Grid::flip_horizontal).collect();
+        rotations.extend(flipped);
+        rotations
+    }
+
+    /// Distinct symmetries of the grid, e.g. a single one for a fully symmetric pattern
+    pub fn unique_symmetries(&self) -> Vec<Grid<T>>
+    where
+        T: PartialEq,
+    {
+        let mut unique: Vec<Grid<T>> = Vec::with_capacity(8);
+        for grid in self.symmetries() {
+            if !unique.contains(&grid) {
+                unique.push(grid);
+            }
+        }
+        unique
+    }
+}
+
+impl<T> Grid<T> {
+    /// Borrowed rectangle of the grid, or `None` if it does not fit
+    pub fn view(&self, origin: Point, width: usize, height: usize) -> Option<GridView<'_, T>> {
+        let fits = width == 0 && height == 0
+            || self.contains(origin)
+                && self.contains(Point::new(origin.x + width as i32 - 1, origin.y + height as i32 - 1));
+        fits.then_some(GridView { grid: self, origin, width, height })
+    }
+
+    /// Every `width` x `height` view that fits in the grid, row by row
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = GridView<'_, T>> {
+        let xs = (self.width() + 1).saturating_sub(width);
+        let ys = (self.height() + 1).saturating_sub(height);
+        (0..ys as i32)
+            .flat_map(move |y| (0..xs as i32).map(move |x| Point::new(x, y)))
+            .filter_map(move |origin| self.view(origin, width, height))
+    }
+}
+
+/// Rectangular window into a `Grid`, addressed relative to its own top-left corner
+#[derive(Debug, Clone, Copy)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Point,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Top-left corner of the view in the underlying grid
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    pub fn get(&self, p: Point) -> Option<&'a T> {
+        if !self.contains(p) {
+            return None;
+        }
+        self.grid.get(Point::new(self.origin.x + p.x, self.origin.y + p.y))
+    }
+
+    pub fn row(&self, y: usize) -> &'a [T] {
+        let start = self.origin.x as usize;
+        &self.grid.row(self.origin.y as usize + y)[start..start + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// Iterate over the cells of the view with positions relative to the view
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &'a T)> + '_ {
+        self.rows().enumerate().flat_map(|(y, row)| {
+            row.iter().enumerate().map(move |(x, cell)| (Point::new(x as i32, y as i32), cell))
+        })
+    }
+
+    /// Copy the view into its own grid
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_vec(self.width, self.height, self.rows().flatten().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERN: &str = "\
+ab
+cd
+ef";
+
+    #[test]
+    fn test_rotations() {
+        let grid = Grid::parse_chars(PATTERN);
+        assert_eq!(grid.rotate_right().to_string(), "eca\nfdb");
+        assert_eq!(grid.rotate_left().to_string(), "bdf\nace");
+        assert_eq!(grid.rotate_180().to_string(), "fe\ndc\nba");
+        assert_eq!(grid.rotate_right().rotate_right(), grid.rotate_180());
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+    }
+
+    #[test]
+    fn test_flips_and_transpose() {
+        let grid = Grid::parse_chars(PATTERN);
+        assert_eq!(grid.flip_horizontal().to_string(), "ba\ndc\nfe");
+        assert_eq!(grid.flip_vertical().to_string(), "ef\ncd\nab");
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf");
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(grid.flip_horizontal().flip_vertical(), grid.rotate_180());
+    }
+
+    #[test]
+    fn test_symmetries() {
+        let grid = Grid::parse_chars(PATTERN);
+        let symmetries = grid.symmetries();
+        assert_eq!(symmetries.len(), 8);
+        assert_eq!(symmetries[0], grid);
+        assert!(symmetries.contains(&grid.transpose()));
+        assert!(symmetries.contains(&grid.flip_vertical()));
+        assert_eq!(grid.unique_symmetries().len(), 8);
+
+        let cross = Grid::parse_chars("M.S\n.A.\nM.S");
+        assert_eq!(cross.unique_symmetries().len(), 4);
+        assert_eq!(Grid::parse_chars("#").unique_symmetries().len(), 1);
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::parse_chars("abcd\nefgh\nijkl");
+        let view = grid.view(Point::new(1, 1), 2, 2).unwrap();
+        assert_eq!(view.get(Point::new(0, 0)), Some(&'f'));
+        assert_eq!(view.get(Point::new(1, 1)), Some(&'k'));
+        assert_eq!(view.get(Point::new(2, 0)), None);
+        assert_eq!(view.row(1), &['j', 'k']);
+        assert_eq!(view.to_grid().to_string(), "fg\njk");
+        assert_eq!(view.iter().map(|(_, &c)| c).collect::<String>(), "fgjk");
+
+        assert!(grid.view(Point::new(3, 0), 2, 1).is_none());
+        assert_eq!(grid.windows(2, 2).count(), 6);
+        assert_eq!(grid.windows(4, 3).count(), 1);
+        assert_eq!(grid.windows(5, 1).count(), 0);
+    }
+}
@@ -0,0 +1,392 @@
+use super::math::{crt, gcd, mod_inverse};
+
+/// Exact fraction with a positive denominator, in lowest terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    pub num: i64,
+    pub den: i64,
+}
+
+impl Ratio {
+    pub fn new(num: i64, den: i64) -> Self {
+        assert!(den != 0, "zero denominator");
+        let g = gcd(num, den) * den.signum();
+        Self { num: num / g, den: den / g }
+    }
+
+    pub fn to_integer(self) -> Option<i64> {
+        (self.den == 1).then_some(self.num)
+    }
+}
+
+/// What kind of solution set a linear system has
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Ratio>),
+    None,
+    /// Every choice of the `free` variables extends to a solution
+    Infinite { free: Vec<usize> },
+}
+
+/// `min_cost_nonnegative` can't search a system where several free variables grow without bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unbounded;
+
+/// System `matrix * x = rhs` with integer coefficients, reduced by fraction-free
+/// Gauss-Jordan elimination: each pivot row has a single non-zero coefficient
+/// among the pivot variables, and it is positive.
+#[derive(Debug, Clone)]
+pub struct LinearSystem {
+    /// Augmented rows, the right-hand side last
+    rows: Vec<Vec<i64>>,
+    /// Variable of each of the first `pivots.len()` rows
+    pivots: Vec<usize>,
+    vars: usize,
+}
+
+/// `a * x - b * y`, panicking instead of overflowing
+fn combine(a: i64, x: i64, b: i64, y: i64) -> i64 {
+    a.checked_mul(x).and_then(|ax| ax.checked_sub(b.checked_mul(y)?)).expect("coefficient overflow")
+}
+
+impl LinearSystem {
+    pub fn new(matrix: &[Vec<i64>], rhs: &[i64]) -> Self {
+        assert_eq!(matrix.len(), rhs.len(), "one right-hand side per equation");
+        let vars = matrix.first().map_or(0, |row| row.len());
+        let mut rows: Vec<Vec<i64>> = matrix
+            .iter()
+            .zip(rhs)
+            .map(|(row, &b)| {
+                assert_eq!(row.len(), vars, "rows must have the same length");
+                row.iter().copied().chain([b]).collect()
+            })
+            .collect();
+
+        let mut pivots = Vec::new();
+        for col in 0..vars {
+            let r = pivots.len();
+            let Some(found) = (r..rows.len()).find(|&i| rows[i][col] != 0) else {
+                continue;
+            };
+            rows.swap(r, found);
+            if rows[r][col] < 0 {
+                rows[r].iter_mut().for_each(|c| *c = -*c);
+            }
+            for i in 0..rows.len() {
+                if i == r || rows[i][col] == 0 {
+                    continue;
+                }
+                let g = gcd(rows[r][col], rows[i][col]);
+                let (keep, subtract) = (rows[r][col] / g, rows[i][col] / g);
+                let pivot_row = rows[r].clone();
+                for (c, &q) in rows[i].iter_mut().zip(&pivot_row) {
+                    *c = combine(*c, keep, q, subtract);
+                }
+                // keep the numbers small
+                let g = rows[i].iter().fold(0, |g, &c| gcd(g, c));
+                if g > 1 {
+                    rows[i].iter_mut().for_each(|c| *c /= g);
+                }
+            }
+            pivots.push(col);
+        }
+        Self { rows, pivots, vars }
+    }
+
+    fn consistent(&self) -> bool {
+        self.rows[self.pivots.len()..].iter().all(|row| row[self.vars] == 0)
+    }
+
+    fn free_vars(&self) -> Vec<usize> {
+        (0..self.vars).filter(|v| !self.pivots.contains(v)).collect()
+    }
+
+    pub fn solution(&self) -> Solution {
+        if !self.consistent() {
+            return Solution::None;
+        }
+        let free = self.free_vars();
+        if !free.is_empty() {
+            return Solution::Infinite { free };
+        }
+        let mut x = vec![Ratio::new(0, 1); self.vars];
+        for (row, &var) in self.rows.iter().zip(&self.pivots) {
+            x[var] = Ratio::new(row[self.vars], row[var]);
+        }
+        Solution::Unique(x)
+    }
+
+    /// Non-negative integer solution minimizing `costs · x`, for non-negative costs.
+    ///
+    /// Free variables that appear in no equation are left at 0. With a single other free
+    /// variable every pivot variable is an affine function of it, so integrality becomes
+    /// congruences on it and non-negativity an interval, with the cheapest solution at one
+    /// end of that interval. With several, all but one are enumerated up to a bound taken
+    /// from an equation whose coefficients are all non-negative; `Err(Unbounded)` if more
+    /// than one of them has no such bound.
+    pub fn min_cost_nonnegative(&self, costs: &[i64]) -> Result<Option<Vec<i64>>, Unbounded> {
+        assert_eq!(costs.len(), self.vars, "one cost per variable");
+        assert!(costs.iter().all(|&c| c >= 0), "costs must be non-negative");
+        if !self.consistent() {
+            return Ok(None);
+        }
+        let rank = self.pivots.len();
+        let pivot_rows = &self.rows[..rank];
+        let free: Vec<usize> =
+            self.free_vars().into_iter().filter(|&f| pivot_rows.iter().any(|row| row[f] != 0)).collect();
+        let rhs: Vec<i64> = pivot_rows.iter().map(|row| row[self.vars]).collect();
+        if free.len() <= 1 {
+            return Ok(self.min_cost_one_free(&rhs, free.first().copied(), costs));
+        }
+
+        // leave the unbounded variable, or else the one with the widest range, to the exact search
+        let bounds: Vec<Option<i64>> = free.iter().map(|&f| self.upper_bound(f, &free)).collect();
+        let last = match bounds.iter().filter(|bound| bound.is_none()).count() {
+            0 => (0..free.len()).max_by_key(|&i| bounds[i]).unwrap(),
+            1 => bounds.iter().position(Option::is_none).unwrap(),
+            _ => return Err(Unbounded),
+        };
+        let enumerated: Vec<(usize, i64)> =
+            (0..free.len()).filter(|&i| i != last).map(|i| (free[i], bounds[i].unwrap())).collect();
+        if enumerated.iter().any(|&(_, bound)| bound < 0) {
+            return Ok(None);
+        }
+
+        let cost = |x: &[i64]| x.iter().zip(costs).map(|(&v, &c)| v as i128 * c as i128).sum::<i128>();
+        let mut best: Option<Vec<i64>> = None;
+        let mut values = vec![0; enumerated.len()];
+        loop {
+            let reduced: Vec<i64> = (0..rank)
+                .map(|i| {
+                    let fixed = enumerated.iter().zip(&values);
+                    fixed.fold(rhs[i], |b, (&(f, _), &v)| combine(b, 1, pivot_rows[i][f], v))
+                })
+                .collect();
+            if let Some(mut x) = self.min_cost_one_free(&reduced, Some(free[last]), costs) {
+                for (&(f, _), &v) in enumerated.iter().zip(&values) {
+                    x[f] = v;
+                }
+                if best.as_ref().is_none_or(|best| cost(&x) < cost(best)) {
+                    best = Some(x);
+                }
+            }
+            // next assignment, counting up like an odometer
+            let Some(i) = (0..values.len()).find(|&i| values[i] < enumerated[i].1) else {
+                return Ok(best);
+            };
+            values[i] += 1;
+            values[..i].iter_mut().for_each(|v| *v = 0);
+        }
+    }
+
+    /// Largest value of free variable `f` that keeps the pivot variables non-negative, if some
+    /// pivot row has only non-negative coefficients among the `free` variables, a positive one for `f`
+    fn upper_bound(&self, f: usize, free: &[usize]) -> Option<i64> {
+        self.rows[..self.pivots.len()]
+            .iter()
+            .filter(|row| row[f] > 0 && free.iter().all(|&k| row[k] >= 0))
+            .map(|row| row[self.vars].div_euclid(row[f]))
+            .min()
+    }
+
+    /// Cheapest solution with every free variable but `free` fixed, their terms moved into `rhs`
+    fn min_cost_one_free(&self, rhs: &[i64], free: Option<usize>, costs: &[i64]) -> Option<Vec<i64>> {
+        let rank = self.pivots.len();
+        let pivot_rows = &self.rows[..rank];
+
+        // pivot variable of row i is (rhs_i - a_i * t) / p_i for the free variable t
+        let b = |i: usize| rhs[i];
+        let a = |i: usize| free.map_or(0, |f| pivot_rows[i][f]);
+        let p = |i: usize| pivot_rows[i][self.pivots[i]];
+
+        // a_i * t ≡ b_i (mod p_i)
+        let mut congruences = Vec::new();
+        for i in 0..rank {
+            let g = gcd(a(i), p(i));
+            if b(i) % g != 0 {
+                return None;
+            }
+            let m = p(i) / g;
+            let inverse = mod_inverse(a(i) / g, m)?;
+            congruences.push((((b(i) / g) as i128 * inverse as i128).rem_euclid(m as i128) as i64, m));
+        }
+        let (residue, step) = crt(&congruences)?;
+
+        // t = residue + step * k with a_i * t <= b_i; t >= 0 is k >= 0 as the residue is in 0..step
+        let mut low = 0;
+        let mut high: Option<i64> = None;
+        for i in 0..rank {
+            let coef = a(i).checked_mul(step).expect("coefficient overflow");
+            let bound = combine(b(i), 1, a(i), residue);
+            match coef.signum() {
+                0 if bound < 0 => return None,
+                1 => high = Some(high.map_or(bound.div_euclid(coef), |h| h.min(bound.div_euclid(coef)))),
+                -1 => low = low.max(-(bound.div_euclid(-coef))),
+                _ => {}
+            }
+        }
+        if high.is_some_and(|high| high < low) {
+            return None;
+        }
+
+        let solve_at = |k: i64| {
+            let t = residue + step * k;
+            let mut x = vec![0; self.vars];
+            if let Some(f) = free {
+                x[f] = t;
+            }
+            for i in 0..rank {
+                x[self.pivots[i]] = combine(b(i), 1, a(i), t) / p(i);
+            }
+            x
+        };
+        let cost = |x: &[i64]| x.iter().zip(costs).map(|(&v, &c)| v as i128 * c as i128).sum::<i128>();
+        let cheapest = solve_at(low);
+        match high {
+            Some(high) if high != low => {
+                let other = solve_at(high);
+                Some(if cost(&other) < cost(&cheapest) { other } else { cheapest })
+            }
+            _ => Some(cheapest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique() {
+        let system = LinearSystem::new(&[vec![94, 22], vec![34, 67]], &[8400, 5400]);
+        assert_eq!(system.solution(), Solution::Unique(vec![Ratio::new(80, 1), Ratio::new(40, 1)]));
+        assert_eq!(system.min_cost_nonnegative(&[3, 1]).unwrap(), Some(vec![80, 40]));
+
+        let fractional = LinearSystem::new(&[vec![2, 0], vec![0, 3]], &[1, 2]);
+        assert_eq!(fractional.solution(), Solution::Unique(vec![Ratio::new(1, 2), Ratio::new(2, 3)]));
+        assert_eq!(fractional.min_cost_nonnegative(&[1, 1]).unwrap(), None);
+        assert_eq!(Ratio::new(4, -6), Ratio::new(-2, 3));
+        assert_eq!(Ratio::new(-6, -3).to_integer(), Some(2));
+
+        let negative = LinearSystem::new(&[vec![1, 1], vec![1, -1]], &[0, 2]);
+        assert_eq!(negative.solution(), Solution::Unique(vec![Ratio::new(1, 1), Ratio::new(-1, 1)]));
+        assert_eq!(negative.min_cost_nonnegative(&[1, 1]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_no_solution() {
+        let system = LinearSystem::new(&[vec![1, 2], vec![2, 4]], &[3, 7]);
+        assert_eq!(system.solution(), Solution::None);
+        assert_eq!(system.min_cost_nonnegative(&[1, 1]).unwrap(), None);
+        // more equations than variables
+        let system = LinearSystem::new(&[vec![1], vec![1]], &[1, 2]);
+        assert_eq!(system.solution(), Solution::None);
+    }
+
+    #[test]
+    fn test_infinite() {
+        // 2a + b = 4 twice over: (0, 4), (1, 2) or (2, 0)
+        let system = LinearSystem::new(&[vec![2, 1], vec![2, 1]], &[4, 4]);
+        assert_eq!(system.solution(), Solution::Infinite { free: vec![1] });
+        assert_eq!(system.min_cost_nonnegative(&[3, 1]).unwrap(), Some(vec![0, 4]));
+        assert_eq!(system.min_cost_nonnegative(&[1, 3]).unwrap(), Some(vec![2, 0]));
+
+        // 4a + 6b = 26 has integer solutions only for odd b: (5, 1) and (2, 3)
+        let system = LinearSystem::new(&[vec![4, 6]], &[26]);
+        assert_eq!(system.min_cost_nonnegative(&[1, 1]).unwrap(), Some(vec![2, 3]));
+        assert_eq!(system.min_cost_nonnegative(&[1, 5]).unwrap(), Some(vec![5, 1]));
+        assert_eq!(LinearSystem::new(&[vec![4, 6]], &[25]).min_cost_nonnegative(&[1, 1]).unwrap(), None);
+        assert_eq!(LinearSystem::new(&[vec![4, 6]], &[2]).min_cost_nonnegative(&[1, 1]).unwrap(), None);
+
+        // a - b = 2 has no upper bound, and c appears nowhere
+        let system = LinearSystem::new(&[vec![1, -1, 0]], &[2]);
+        assert_eq!(system.solution(), Solution::Infinite { free: vec![1, 2] });
+        assert_eq!(system.min_cost_nonnegative(&[1, 1, 1]).unwrap(), Some(vec![2, 0, 0]));
+
+        let empty = LinearSystem::new(&[vec![0, 0]], &[0]);
+        assert_eq!(empty.min_cost_nonnegative(&[3, 1]).unwrap(), Some(vec![0, 0]));
+        assert_eq!(LinearSystem::new(&[vec![0, 0]], &[5]).solution(), Solution::None);
+    }
+
+    #[test]
+    fn test_agrees_with_brute_force() {
+        for a in [(0, 0), (1, 2), (2, 4), (3, 1), (-1, 2)] {
+            for b in [(0, 0), (2, 4), (5, 10), (1, 1), (0, 3)] {
+                for target in [(0, 0), (6, 12), (7, 14), (10, 9), (3, 9)] {
+                    let system = LinearSystem::new(&[vec![a.0, b.0], vec![a.1, b.1]], &[target.0, target.1]);
+                    let expected = (0..=20)
+                        .flat_map(|i| (0..=20).map(move |j| (i, j)))
+                        .filter(|&(i, j)| a.0 * i + b.0 * j == target.0 && a.1 * i + b.1 * j == target.1)
+                        .min_by_key(|&(i, j)| (3 * i + j, i));
+                    let found = system.min_cost_nonnegative(&[3, 1]).unwrap();
+                    assert_eq!(found.map(|x| 3 * x[0] + x[1]), expected.map(|(i, j)| 3 * i + j), "{a:?} {b:?} {target:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_several_free_variables() {
+        // a + b + c = 5: c is the cheapest way to make up the sum
+        let system = LinearSystem::new(&[vec![1, 1, 1]], &[5]);
+        assert_eq!(system.min_cost_nonnegative(&[3, 2, 1]), Ok(Some(vec![0, 0, 5])));
+        assert_eq!(system.min_cost_nonnegative(&[1, 2, 3]), Ok(Some(vec![5, 0, 0])));
+
+        // 2a + 3b + 4c = 7 and a + d = 2, b is only bounded by the first equation
+        let system = LinearSystem::new(&[vec![2, 3, 4, 0], vec![1, 0, 0, 1]], &[7, 2]);
+        assert_eq!(system.min_cost_nonnegative(&[1, 1, 1, 1]), Ok(Some(vec![2, 1, 0, 0])));
+        assert_eq!(system.min_cost_nonnegative(&[5, 1, 1, 1]), Ok(Some(vec![0, 1, 1, 2])));
+
+        // a - b = 1 and c + d = 2: b grows without bound, d is at most 2
+        let system = LinearSystem::new(&[vec![1, -1, 0, 0], vec![0, 0, 1, 1]], &[1, 2]);
+        assert_eq!(system.min_cost_nonnegative(&[1, 1, 3, 1]), Ok(Some(vec![1, 0, 0, 2])));
+        assert_eq!(system.min_cost_nonnegative(&[1, 1, 1, 3]), Ok(Some(vec![1, 0, 2, 0])));
+
+        // a + b + c = -1 has no non-negative solution
+        let system = LinearSystem::new(&[vec![1, 1, 1]], &[-1]);
+        assert_eq!(system.min_cost_nonnegative(&[1, 1, 1]), Ok(None));
+
+        // a = 1 + b + c lets both b and c grow forever
+        let system = LinearSystem::new(&[vec![1, -1, -1]], &[1]);
+        assert_eq!(system.min_cost_nonnegative(&[1, 1, 1]), Err(Unbounded));
+    }
+
+    #[test]
+    fn test_several_free_variables_brute_force() {
+        let coefficients = [-1, 0, 1, 2, 3];
+        let pick = |seed: usize, n: usize| -> Vec<i64> {
+            (0..n).map(|k| coefficients[(seed / 5usize.pow(k as u32)) % 5]).collect()
+        };
+        for seed in (0..5usize.pow(8)).step_by(211) {
+            let matrix = [pick(seed, 4), pick(seed / 625, 4)];
+            for rhs in [[0, 0], [4, 2], [7, 3], [5, -1]] {
+                let system = LinearSystem::new(&matrix, &rhs);
+                let costs = [2, 1, 3, 1];
+                let Ok(found) = system.min_cost_nonnegative(&costs) else {
+                    continue;
+                };
+                let cost = |x: &[i64]| x.iter().zip(&costs).map(|(v, c)| v * c).sum::<i64>();
+                let expected = (0..7i64.pow(4))
+                    .map(|n| [n % 7, n / 7 % 7, n / 49 % 7, n / 343])
+                    .filter(|x| matrix.iter().zip(&rhs).all(|(row, &b)| row.iter().zip(x).map(|(r, v)| r * v).sum::<i64>() == b))
+                    .map(|x| cost(&x))
+                    .min();
+                match &found {
+                    Some(x) => {
+                        assert!(x.iter().all(|&v| v >= 0), "{matrix:?} {rhs:?}");
+                        for (row, &b) in matrix.iter().zip(&rhs) {
+                            assert_eq!(row.iter().zip(x).map(|(r, v)| r * v).sum::<i64>(), b, "{matrix:?} {rhs:?}");
+                        }
+                        // the brute force only looks at values below 7
+                        if x.iter().all(|&v| v < 7) {
+                            assert_eq!(Some(cost(x)), expected, "{matrix:?} {rhs:?}");
+                        } else {
+                            assert!(expected.is_none_or(|e| e >= cost(x)), "{matrix:?} {rhs:?}");
+                        }
+                    }
+                    None => assert_eq!(expected, None, "{matrix:?} {rhs:?}"),
+                }
+            }
+        }
+    }
+}
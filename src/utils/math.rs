@@ -0,0 +1,135 @@
+/// Greatest common divisor, always non-negative; `gcd(0, 0)` is 0
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, always non-negative; 0 if either argument is 0
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    assert!(m > 0, "modulus must be positive");
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base^exp mod m` in `0..m`, by repeated squaring
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    assert!(m > 0, "modulus must be positive");
+    let m = m as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// Solve `x ≡ r (mod m)` for every `(r, m)` in `congruences`, returning `(x, lcm of the moduli)`
+/// with `x` the smallest non-negative solution. The moduli need not be coprime;
+/// `None` if the congruences contradict each other.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut x, mut modulus) = (0i128, 1i128);
+    for &(r, m) in congruences {
+        assert!(m > 0, "modulus must be positive");
+        // x + modulus * k ≡ r (mod m)  <=>  modulus * k ≡ r - x (mod m)
+        let (g, p, _) = extended_gcd(modulus as i64, m);
+        let diff = r as i128 - x;
+        if diff % g as i128 != 0 {
+            return None;
+        }
+        let step = (m / g) as i128;
+        let k = (diff / g as i128 * p as i128).rem_euclid(step);
+        x += modulus * k;
+        modulus *= step;
+        x = x.rem_euclid(modulus);
+    }
+    Some((x as i64, modulus as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(101, 103), 10403);
+        assert_eq!(lcm(0, 5), 0);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (46, 240), (-240, 46), (17, 5), (0, 9), (9, 0), (-3, -9)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(101, 103), Some(51));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 7), 6);
+        assert_eq!(mod_pow(5, 0, 7), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        // Fermat: a^(p-1) ≡ 1 for a large prime modulus, which needs 128-bit products
+        assert_eq!(mod_pow(123_456_789, (1 << 61) - 2, (1 << 61) - 1), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(12, 101), (35, 103)]).map(|(x, _)| (x % 101, x % 103)), Some((12, 35)));
+        // moduli sharing a factor
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(3, 4), (4, 6)]), None);
+        assert_eq!(crt(&[(-1, 10), (1, 1)]), Some((9, 10)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+}
@@ -0,0 +1,165 @@
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Cache for recursive functions. A function takes `&mut Memo` and wraps its body in
+/// `get_or_compute`, passing the memo on to its recursive calls.
+///
+/// Keys are looked up by reference, so a `Memo<String, _>` is queried with `&str`
+/// and only allocates when a new result is stored.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    limit: Option<usize>,
+    /// Keys in insertion order, only tracked when there is a limit
+    order: VecDeque<K>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self { cache: HashMap::new(), limit: None, order: VecDeque::new() }
+    }
+
+    /// Memo that holds at most `limit` results, dropping the oldest one to make room for a new one.
+    /// Recursions that reuse recent results, like most top-down dynamic programming, stay fast
+    /// even when the limit is far below the number of subproblems.
+    pub fn with_limit(limit: usize) -> Self {
+        assert!(limit > 0, "a memo needs room for at least one result");
+        Self { cache: HashMap::new(), limit: Some(limit), order: VecDeque::new() }
+    }
+
+    /// Cached result for `key`, or the result of `compute` which is then cached.
+    /// `compute` gets the memo back for recursive calls.
+    pub fn get_or_compute<Q>(&mut self, key: &Q, compute: impl FnOnce(&mut Self) -> V) -> V
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = K> + ?Sized,
+    {
+        if let Some(value) = self.cache.get(key) {
+            return value.clone();
+        }
+        let value = compute(self);
+        self.insert(key.to_owned(), value.clone());
+        value
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.cache.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(cached) = self.cache.get_mut(&key) {
+            *cached = value;
+            return;
+        }
+        if let Some(limit) = self.limit {
+            if self.cache.len() >= limit {
+                let oldest = self.order.pop_front().unwrap();
+                self.cache.remove(&oldest);
+            }
+            self.order.push_back(key.clone());
+        }
+        self.cache.insert(key, value);
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.get_or_compute(&n, |memo| fibonacci(n - 1, memo) + fibonacci(n - 2, memo))
+    }
+
+    // number of ways to split `s` into pieces of length 1 or 2
+    fn splits(s: &str, memo: &mut Memo<String, u64>, calls: &mut u32) -> u64 {
+        if s.len() < 2 {
+            return 1;
+        }
+        memo.get_or_compute(s, |memo| {
+            *calls += 1;
+            splits(&s[1..], memo, calls) + splits(&s[2..], memo, calls)
+        })
+    }
+
+    #[test]
+    fn test_recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(90, &mut memo), 2880067194370816120);
+        assert_eq!(memo.len(), 89);
+        assert_eq!(memo.get(&10), Some(&55));
+    }
+
+    #[test]
+    fn test_borrowed_keys() {
+        let mut memo = Memo::new();
+        let mut calls = 0;
+        assert_eq!(splits("abcdefghij", &mut memo, &mut calls), 89);
+        assert_eq!(calls, 9);
+        assert_eq!(splits("cdefghij", &mut memo, &mut calls), 34);
+        assert_eq!(calls, 9);
+        assert_eq!(memo.get("ij"), Some(&2));
+
+        memo.clear();
+        assert!(memo.is_empty());
+        splits("hij", &mut memo, &mut calls);
+        assert_eq!(calls, 11);
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut memo = Memo::with_limit(10);
+        for n in 0..25 {
+            memo.insert(n, n);
+        }
+        assert_eq!(memo.len(), 10);
+        assert_eq!(memo.get(&14), None);
+        assert_eq!(memo.get(&15), Some(&15));
+
+        // updating a result doesn't make room for another
+        memo.insert(20, 0);
+        memo.insert(25, 25);
+        assert_eq!(memo.len(), 10);
+        assert_eq!((memo.get(&15), memo.get(&20)), (None, Some(&0)));
+    }
+
+    #[test]
+    fn test_recursion_at_limit() {
+        // both recursions only look back at the two latest results
+        let mut memo = Memo::with_limit(2);
+        assert_eq!(fibonacci(90, &mut memo), 2880067194370816120);
+        assert_eq!(memo.len(), 2);
+
+        let mut memo = Memo::with_limit(2);
+        let mut calls = 0;
+        let text = "a".repeat(60);
+        assert_eq!(splits(&text, &mut memo, &mut calls), 2504730781961);
+        assert_eq!(calls, 59);
+    }
+}
@@ -0,0 +1,563 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use super::visited::VisitedSet;
+
+/// Edge weight usable by the shortest-path searches
+pub trait Cost: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(impl Cost for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+impl_cost!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Best known costs from the starts, with the predecessors used to reach every state.
+/// Only the first predecessor is kept unless the search was asked to record all of them.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<T, C> {
+    distances: HashMap<T, C>,
+    parents: HashMap<T, Vec<T>>,
+    targets: Vec<T>,
+    all_predecessors: bool,
+}
+
+impl<T: Hash + Eq + Clone, C: Cost> ShortestPaths<T, C> {
+    fn new(all_predecessors: bool) -> Self {
+        Self { distances: HashMap::new(), parents: HashMap::new(), targets: Vec::new(), all_predecessors }
+    }
+
+    /// Record `cost` for `state` if it improves on the known one, returning `true` if it did.
+    /// Equal-cost predecessors are added when recording all of them.
+    fn relax(&mut self, state: &T, parent: Option<&T>, cost: C) -> bool {
+        match self.distances.get(state) {
+            Some(&known) if known < cost => false,
+            Some(&known) if known == cost => {
+                if let (true, Some(parent)) = (self.all_predecessors, parent) {
+                    let parents = self.parents.entry(state.clone()).or_default();
+                    if !parents.contains(parent) {
+                        parents.push(parent.clone());
+                    }
+                }
+                false
+            }
+            _ => {
+                self.distances.insert(state.clone(), cost);
+                self.parents.insert(state.clone(), parent.into_iter().cloned().collect());
+                true
+            }
+        }
+    }
+
+    /// Cost of the cheapest known path to `state`.
+    /// Exact for every state settled before the search stopped.
+    pub fn distance(&self, state: &T) -> Option<C> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<T, C> {
+        &self.distances
+    }
+
+    /// First state matching the target predicate, if the search reached one
+    pub fn target(&self) -> Option<&T> {
+        self.targets.first()
+    }
+
+    /// Every target state reached at the optimal cost.
+    /// Only the first one is found unless the search recorded all predecessors.
+    pub fn targets(&self) -> &[T] {
+        &self.targets
+    }
+
+    pub fn target_distance(&self) -> Option<C> {
+        self.distance(self.target()?)
+    }
+
+    /// Predecessors of `state` on its cheapest paths
+    pub fn predecessors(&self, state: &T) -> &[T] {
+        self.parents.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// Cheapest path from a start to `state`, both included
+    pub fn path_to(&self, state: &T) -> Option<Vec<T>> {
+        self.distances.get(state)?;
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.predecessors(path.last().unwrap()).first() {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Cheapest path from a start to the target
+    pub fn path(&self) -> Option<Vec<T>> {
+        self.path_to(self.target()?)
+    }
+
+    /// Optimal paths from the starts to every target
+    pub fn dag(&self) -> PathDag<T> {
+        self.dag_to(self.targets.iter().cloned())
+    }
+
+    /// Optimal paths from the starts to the given end states; unreached ends are ignored
+    pub fn dag_to(&self, ends: impl IntoIterator<Item = T>) -> PathDag<T> {
+        let ends: Vec<T> = ends.into_iter().filter(|end| self.distances.contains_key(end)).collect();
+        let mut predecessors = HashMap::new();
+        let mut stack = ends.clone();
+        while let Some(state) = stack.pop() {
+            if predecessors.contains_key(&state) {
+                continue;
+            }
+            let parents = self.predecessors(&state).to_vec();
+            stack.extend(parents.iter().cloned());
+            predecessors.insert(state, parents);
+        }
+
+        // depth-first post-order, as distance ties across zero-cost edges don't say which state comes first
+        let mut order = Vec::with_capacity(predecessors.len());
+        let mut entered = HashSet::new();
+        let mut stack: Vec<(T, bool)> = ends.iter().map(|end| (end.clone(), false)).collect();
+        while let Some((state, finished)) = stack.pop() {
+            if finished {
+                order.push(state);
+            } else if entered.insert(state.clone()) {
+                let parents = predecessors[&state].iter().filter(|p| !entered.contains(*p));
+                let parents: Vec<(T, bool)> = parents.map(|p| (p.clone(), false)).collect();
+                stack.push((state, true));
+                stack.extend(parents);
+            }
+        }
+        PathDag { predecessors, ends, order }
+    }
+}
+
+/// Every optimal path to a set of end states, stored as the predecessors of each state on them.
+/// States without predecessors are the starts.
+#[derive(Debug, Clone)]
+pub struct PathDag<T> {
+    predecessors: HashMap<T, Vec<T>>,
+    ends: Vec<T>,
+    /// States in topological order, each one after its predecessors
+    order: Vec<T>,
+}
+
+impl<T: Hash + Eq + Clone> PathDag<T> {
+    pub fn ends(&self) -> &[T] {
+        &self.ends
+    }
+
+    pub fn contains(&self, state: &T) -> bool {
+        self.predecessors.contains_key(state)
+    }
+
+    pub fn predecessors(&self, state: &T) -> &[T] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every state on at least one optimal path
+    pub fn nodes(&self) -> impl Iterator<Item = &T> + '_ {
+        self.order.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Number of distinct optimal paths, summed over all ends
+    pub fn count_paths(&self) -> usize {
+        let mut ways: HashMap<&T, usize> = HashMap::with_capacity(self.order.len());
+        for state in &self.order {
+            let preds = self.predecessors(state);
+            let count = if preds.is_empty() { 1 } else { preds.iter().map(|p| ways[p]).sum() };
+            ways.insert(state, count);
+        }
+        self.ends.iter().map(|end| ways[end]).sum()
+    }
+
+    /// Lazily enumerate the optimal paths, from start to end.
+    /// Only the path under construction is kept in memory.
+    pub fn paths(&self) -> OptimalPaths<'_, T> {
+        OptimalPaths { dag: self, stack: vec![(None, 0)] }
+    }
+}
+
+/// Iterator over the paths of a `PathDag`, see `PathDag::paths`
+pub struct OptimalPaths<'a, T> {
+    dag: &'a PathDag<T>,
+    /// Partial path walked backwards from an end, with the next predecessor to try for each state.
+    /// The bottom entry stands for the ends themselves.
+    stack: Vec<(Option<&'a T>, usize)>,
+}
+
+impl<T: Hash + Eq + Clone> Iterator for OptimalPaths<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let dag = self.dag;
+        while let Some((state, next)) = self.stack.last_mut() {
+            let options = match state {
+                Some(state) => dag.predecessors(state),
+                None => dag.ends.as_slice(),
+            };
+            let Some(option) = options.get(*next) else {
+                self.stack.pop();
+                continue;
+            };
+            *next += 1;
+
+            if dag.predecessors(option).is_empty() {
+                let mut path = vec![option.clone()];
+                path.extend(self.stack.iter().rev().filter_map(|(state, _)| state.cloned()));
+                return Some(path);
+            }
+            self.stack.push((Some(option), 0));
+        }
+        None
+    }
+}
+
+/// Dijkstra's algorithm from all starts, stopping once a state matching `is_target` is settled.
+/// `get_neighbors` yields `(next_state, edge_cost)` pairs with non-negative costs.
+pub fn dijkstra<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    dijkstra_with(starts, is_target, get_neighbors, &mut HashSet::new())
+}
+
+/// `dijkstra` with a caller-provided set of settled states, e.g. a `BitGrid` for bounded grids
+pub fn dijkstra_with<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+    settled: &mut impl VisitedSet<T>,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    astar_with(starts, is_target, get_neighbors, |_| C::ZERO, settled)
+}
+
+/// A* search: Dijkstra ordered by `cost + heuristic(state)`.
+/// The heuristic must never overestimate the remaining cost and must be consistent.
+pub fn astar<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+    heuristic: impl Fn(&T) -> C,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    astar_with(starts, is_target, get_neighbors, heuristic, &mut HashSet::new())
+}
+
+/// `astar` with a caller-provided set of settled states
+pub fn astar_with<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+    heuristic: impl Fn(&T) -> C,
+    settled: &mut impl VisitedSet<T>,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    search(starts, is_target, get_neighbors, heuristic, settled, false)
+}
+
+/// Dijkstra's algorithm recording every equal-cost predecessor, so that `ShortestPaths::dag`
+/// describes all optimal paths. The search continues until every target at the optimal cost is found.
+/// Zero-cost cycles are not supported.
+pub fn dijkstra_all<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    dijkstra_all_with(starts, is_target, get_neighbors, &mut HashSet::new())
+}
+
+/// `dijkstra_all` with a caller-provided set of settled states
+pub fn dijkstra_all_with<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+    settled: &mut impl VisitedSet<T>,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    search(starts, is_target, get_neighbors, |_| C::ZERO, settled, true)
+}
+
+fn search<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+    heuristic: impl Fn(&T) -> C,
+    settled: &mut impl VisitedSet<T>,
+    all_predecessors: bool,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    let mut result = ShortestPaths::new(all_predecessors);
+    // the heap orders indices into `queued` so states don't need to implement `Ord`
+    let mut queued = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if result.relax(&start, None, C::ZERO) {
+            heap.push(Reverse((heuristic(&start), C::ZERO, queued.len())));
+            queued.push(Some(start));
+        }
+    }
+
+    let mut best = None;
+    while let Some(Reverse((priority, cost, index))) = heap.pop() {
+        if best.is_some_and(|best| priority > best) {
+            break;
+        }
+        let state = queued[index].take().unwrap();
+        if settled.contains(&state) || result.distances[&state] < cost {
+            continue;
+        }
+        settled.insert(state.clone());
+
+        if is_target(&state) {
+            result.targets.push(state);
+            if !all_predecessors {
+                break;
+            }
+            best = Some(cost);
+            continue;
+        }
+
+        for (next, edge) in get_neighbors(&state) {
+            let next_cost = cost + edge;
+            if settled.contains(&next) {
+                // only an equal-cost predecessor can still be recorded
+                result.relax(&next, Some(&state), next_cost);
+            } else if result.relax(&next, Some(&state), next_cost) {
+                heap.push(Reverse((next_cost + heuristic(&next), next_cost, queued.len())));
+                queued.push(Some(next));
+            }
+        }
+    }
+    result
+}
+
+/// Shortest paths for edge costs that are either `C::ZERO` or `C::ONE`, using a deque instead of a heap
+pub fn zero_one_bfs<T, C, I>(
+    starts: impl IntoIterator<Item = T>,
+    is_target: impl Fn(&T) -> bool,
+    get_neighbors: impl Fn(&T) -> I,
+) -> ShortestPaths<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (T, C)>,
+{
+    let mut result = ShortestPaths::new(false);
+    let mut settled = HashSet::new();
+    let mut deque = VecDeque::new();
+
+    for start in starts {
+        if result.relax(&start, None, C::ZERO) {
+            deque.push_back((start, C::ZERO));
+        }
+    }
+
+    while let Some((state, cost)) = deque.pop_front() {
+        if !settled.insert(state.clone()) {
+            continue;
+        }
+
+        if is_target(&state) {
+            result.targets.push(state);
+            break;
+        }
+
+        for (next, edge) in get_neighbors(&state) {
+            assert!(edge == C::ZERO || edge == C::ONE, "0-1 BFS only supports edge costs of 0 and 1");
+            let next_cost = cost + edge;
+            if !settled.contains(&next) && result.relax(&next, Some(&state), next_cost) {
+                if edge == C::ZERO {
+                    deque.push_front((next, next_cost));
+                } else {
+                    deque.push_back((next, next_cost));
+                }
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::grid::{Grid, Point};
+    use crate::utils::visited::BitGrid;
+
+    const MAP: &str = "\
+13119
+11191
+99111";
+
+    fn weighted_moves(grid: &Grid<u32>) -> impl Fn(&Point) -> Vec<(Point, u32)> + '_ {
+        |p| grid.neighbors(*p).map(|n| (n, grid[n])).collect()
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let grid = Grid::parse(MAP, |c| c.to_digit(10).unwrap());
+        let end = Point::new(4, 2);
+        let paths = dijkstra([Point::new(0, 0)], |p| *p == end, weighted_moves(&grid));
+
+        assert_eq!(paths.target(), Some(&end));
+        assert_eq!(paths.target_distance(), Some(6));
+        let path = paths.path().unwrap();
+        assert_eq!(path.first(), Some(&Point::new(0, 0)));
+        assert_eq!(path.iter().skip(1).map(|&p| grid[p]).sum::<u32>(), 6);
+        assert_eq!(paths.distance(&Point::new(1, 0)), Some(3));
+    }
+
+    #[test]
+    fn test_dijkstra_without_target() {
+        let grid = Grid::parse(MAP, |c| c.to_digit(10).unwrap());
+        let paths = dijkstra_with(
+            [Point::new(0, 0), Point::new(4, 2)],
+            |_| false,
+            weighted_moves(&grid),
+            &mut BitGrid::for_grid(&grid),
+        );
+        assert_eq!(paths.target(), None);
+        assert_eq!(paths.distances().len(), 15);
+        assert_eq!(paths.distance(&Point::new(4, 0)), Some(10));
+        assert_eq!(paths.path_to(&Point::new(3, 2)).unwrap(), vec![Point::new(4, 2), Point::new(3, 2)]);
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let grid = Grid::parse(MAP, |c| c.to_digit(10).unwrap());
+        let end = Point::new(4, 2);
+        let heuristic = |p: &Point| p.manhattan_distance(&end) as u32;
+        let paths = astar([Point::new(0, 0)], |p| *p == end, weighted_moves(&grid), heuristic);
+        assert_eq!(paths.target_distance(), Some(6));
+        // the heuristic prunes states that are too far away
+        assert!(paths.distance(&Point::new(4, 0)).is_none());
+    }
+
+    #[test]
+    fn test_zero_one_bfs() {
+        // walking along a row is free, changing rows costs one
+        let moves = |&(x, y): &(i64, i64)| {
+            [((x + 1, y), 0), ((x - 1, y), 0), ((x, y + 1), 1), ((x, y - 1), 1)]
+                .into_iter()
+                .filter(|((x, y), _)| (0..10).contains(x) && (0..4).contains(y))
+        };
+        let paths = zero_one_bfs([(0, 0)], |&state| state == (9, 3), moves);
+        assert_eq!(paths.target_distance(), Some(3i64));
+        assert_eq!(paths.path().unwrap().len(), 13);
+    }
+
+    #[test]
+    fn test_optimal_path_dag() {
+        let grid = Grid::new(3, 3, 1);
+        let end = Point::new(2, 2);
+        let paths = dijkstra_all([Point::new(0, 0)], |p| *p == end, weighted_moves(&grid));
+        assert_eq!(paths.target_distance(), Some(4));
+        assert_eq!(paths.predecessors(&end).len(), 2);
+
+        let dag = paths.dag();
+        assert_eq!(dag.len(), 9);
+        assert_eq!(dag.count_paths(), 6);
+        let all: HashSet<Vec<Point>> = dag.paths().collect();
+        assert_eq!(all.len(), 6);
+        assert!(all.iter().all(|path| path.len() == 5 && path[0] == Point::new(0, 0) && path[4] == end));
+
+        // a single recorded predecessor leaves one optimal path
+        let single = dijkstra([Point::new(0, 0)], |p| *p == end, weighted_moves(&grid)).dag();
+        assert_eq!(single.count_paths(), 1);
+        assert_eq!(single.paths().next(), paths.path());
+    }
+
+    #[test]
+    fn test_dag_with_several_targets() {
+        let grid = Grid::new(3, 3, 1);
+        let paths = dijkstra_all([Point::new(0, 0)], |p| p.x + p.y == 2, weighted_moves(&grid));
+        assert_eq!(paths.targets().len(), 3);
+        assert!(paths.targets().iter().all(|t| paths.distance(t) == Some(2)));
+
+        let dag = paths.dag();
+        assert_eq!(dag.count_paths(), 4);
+        assert_eq!(dag.paths().count(), 4);
+        assert!(!dag.contains(&Point::new(2, 2)));
+
+        let to_one = paths.dag_to([Point::new(0, 2), Point::new(4, 4)]);
+        assert_eq!(to_one.ends(), &[Point::new(0, 2)]);
+        assert_eq!(to_one.paths().collect::<Vec<_>>(), vec![vec![Point::new(0, 0), Point::new(0, 1), Point::new(0, 2)]]);
+    }
+
+    #[test]
+    fn test_dag_with_zero_cost_edge() {
+        // 1 is as far from the start as the start itself, but must still be counted after it
+        let edges = |&n: &u32| match n {
+            0 => vec![(1, 0), (2, 1)],
+            1 => vec![(2, 1)],
+            _ => vec![],
+        };
+        for _ in 0..10 {
+            let paths = dijkstra_all([0u32], |&n| n == 2, edges);
+            assert_eq!(paths.target_distance(), Some(1u32));
+            let dag = paths.dag();
+            assert_eq!(dag.count_paths(), 2);
+            assert_eq!(dag.nodes().position(|&n| n == 0), Some(0));
+            assert_eq!(dag.nodes().last(), Some(&2));
+        }
+    }
+
+    #[test]
+    fn test_unreachable_target() {
+        let paths: ShortestPaths<u32, u32> = dijkstra([0], |&n| n == 5, |&n| (n < 3).then_some((n + 1, 1)));
+        assert_eq!(paths.target(), None);
+        assert_eq!(paths.path(), None);
+        assert_eq!(paths.distance(&3), Some(3));
+    }
+}
@@ -0,0 +1,405 @@
+/// Trie over byte strings, storing nodes in one arena with sorted child lists
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+    lengths: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: Vec<(u8, usize)>,
+    /// Id of the pattern ending at this node
+    pattern: Option<usize>,
+    depth: usize,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self { nodes: vec![Node::default()], lengths: Vec::new() }
+    }
+
+    fn child(&self, node: usize, byte: u8) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children.binary_search_by_key(&byte, |&(b, _)| b).ok().map(|i| children[i].1)
+    }
+
+    /// Add a non-empty pattern, returning its id.
+    /// Ids count up from 0 and a duplicate pattern gets the id of its first copy.
+    pub fn insert(&mut self, pattern: impl AsRef<[u8]>) -> usize {
+        let pattern = pattern.as_ref();
+        assert!(!pattern.is_empty(), "Empty patterns can't be matched");
+        let mut node = 0;
+        for &byte in pattern {
+            node = match self.child(node, byte) {
+                Some(next) => next,
+                None => {
+                    let next = self.nodes.len();
+                    let depth = self.nodes[node].depth + 1;
+                    self.nodes.push(Node { depth, ..Node::default() });
+                    let children = &mut self.nodes[node].children;
+                    let i = children.partition_point(|&(b, _)| b < byte);
+                    children.insert(i, (byte, next));
+                    next
+                }
+            };
+        }
+        if let Some(id) = self.nodes[node].pattern {
+            return id;
+        }
+        let id = self.lengths.len();
+        self.lengths.push(pattern.len());
+        self.nodes[node].pattern = Some(id);
+        id
+    }
+
+    /// Number of distinct patterns
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    pub fn pattern_len(&self, id: usize) -> usize {
+        self.lengths[id]
+    }
+
+    /// Id of the pattern equal to `text`
+    pub fn find(&self, text: &[u8]) -> Option<usize> {
+        let node = text.iter().try_fold(0, |node, &byte| self.child(node, byte))?;
+        self.nodes[node].pattern
+    }
+
+    pub fn contains(&self, text: &[u8]) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Patterns that are prefixes of `text` as `(id, length)`, shortest first
+    pub fn prefixes<'a>(&'a self, text: &'a [u8]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let mut node = Some(0);
+        text.iter()
+            .map_while(move |&byte| {
+                node = self.child(node?, byte);
+                node
+            })
+            .filter_map(|node| self.nodes[node].pattern.map(|id| (id, self.nodes[node].depth)))
+    }
+}
+
+/// Occurrence of pattern `pattern` at `text[start..end]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Aho-Corasick automaton finding all occurrences of a set of patterns in one pass over a text
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    trie: Trie,
+    /// Node of the longest proper suffix that is also in the trie
+    fail: Vec<usize>,
+    /// Nearest node ending a pattern on the failure chain, starting with the node itself
+    output: Vec<Option<usize>>,
+}
+
+impl AhoCorasick {
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        let mut trie = Trie::new();
+        for pattern in patterns {
+            trie.insert(pattern);
+        }
+        Self::from_trie(trie)
+    }
+
+    pub fn from_trie(trie: Trie) -> Self {
+        let mut fail = vec![0; trie.nodes.len()];
+        let mut output = vec![None; trie.nodes.len()];
+        // breadth first, so shorter suffixes are done before the nodes that fail to them
+        let mut queue = std::collections::VecDeque::from([0]);
+        while let Some(node) = queue.pop_front() {
+            for &(byte, next) in &trie.nodes[node].children {
+                if node != 0 {
+                    let mut suffix = fail[node];
+                    while suffix != 0 && trie.child(suffix, byte).is_none() {
+                        suffix = fail[suffix];
+                    }
+                    fail[next] = trie.child(suffix, byte).unwrap_or(0);
+                }
+                output[next] = if trie.nodes[next].pattern.is_some() { Some(next) } else { output[fail[next]] };
+                queue.push_back(next);
+            }
+        }
+        Self { trie, fail, output }
+    }
+
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(next) = self.trie.child(node, byte) {
+                return next;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.fail[node];
+        }
+    }
+
+    /// Every occurrence of every pattern, ordered by end position and longest first for equal ends
+    pub fn find_overlapping<'a>(&'a self, text: &'a [u8]) -> Matches<'a> {
+        Matches { automaton: self, text, pos: 0, node: 0, pending: None }
+    }
+
+    /// Whether `text` can be split into a sequence of patterns
+    pub fn can_segment(&self, text: &[u8]) -> bool {
+        let mut reachable = vec![false; text.len() + 1];
+        reachable[0] = true;
+        for m in self.find_overlapping(text) {
+            reachable[m.end] |= reachable[m.start];
+        }
+        reachable[text.len()]
+    }
+
+    /// Number of ways to split `text` into a sequence of patterns
+    pub fn count_segmentations(&self, text: &[u8]) -> u64 {
+        let mut ways = vec![0; text.len() + 1];
+        ways[0] = 1;
+        for m in self.find_overlapping(text) {
+            ways[m.end] += ways[m.start];
+        }
+        ways[text.len()]
+    }
+
+    /// One way to split `text` into patterns, as consecutive matches
+    pub fn segmentation(&self, text: &[u8]) -> Option<Vec<Match>> {
+        let mut last = vec![None; text.len() + 1];
+        let mut reachable = vec![false; text.len() + 1];
+        reachable[0] = true;
+        for m in self.find_overlapping(text) {
+            if reachable[m.start] && !reachable[m.end] {
+                reachable[m.end] = true;
+                last[m.end] = Some(m);
+            }
+        }
+        reachable[text.len()].then(|| backtrack(&last, text.len()))
+    }
+
+    /// A split of `text` into as few patterns as possible
+    pub fn shortest_segmentation(&self, text: &[u8]) -> Option<Vec<Match>> {
+        let mut last = vec![None; text.len() + 1];
+        let mut fewest = vec![usize::MAX; text.len() + 1];
+        fewest[0] = 0;
+        for m in self.find_overlapping(text) {
+            if fewest[m.start] != usize::MAX && fewest[m.start] + 1 < fewest[m.end] {
+                fewest[m.end] = fewest[m.start] + 1;
+                last[m.end] = Some(m);
+            }
+        }
+        (fewest[text.len()] != usize::MAX).then(|| backtrack(&last, text.len()))
+    }
+
+    /// Lazily enumerate every split of `text` into patterns, as many as `count_segmentations`.
+    /// Matches that can't be completed to the end of the text are never tried.
+    pub fn segmentations(&self, text: &[u8]) -> Segmentations {
+        let matches: Vec<Match> = self.find_overlapping(text).collect();
+        // whether text[i..] can be split, filled from the end
+        let mut completes = vec![false; text.len() + 1];
+        completes[text.len()] = true;
+        for m in matches.iter().rev() {
+            completes[m.start] |= completes[m.end];
+        }
+        let mut by_start = vec![Vec::new(); text.len() + 1];
+        for m in matches.into_iter().filter(|m| completes[m.end]) {
+            by_start[m.start].push(m);
+        }
+        Segmentations { by_start, len: text.len(), path: Vec::new(), choices: Vec::new(), done: false }
+    }
+}
+
+/// Follow the match ending at each position back to the start of the text
+fn backtrack(last: &[Option<Match>], end: usize) -> Vec<Match> {
+    let mut matches: Vec<Match> = std::iter::successors(last[end], |m| last[m.start]).collect();
+    matches.reverse();
+    matches
+}
+
+/// Iterator returned by `AhoCorasick::find_overlapping`
+pub struct Matches<'a> {
+    automaton: &'a AhoCorasick,
+    text: &'a [u8],
+    pos: usize,
+    node: usize,
+    /// Next node on the output chain still to report for the current position
+    pending: Option<usize>,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        let automaton = self.automaton;
+        loop {
+            if let Some(node) = self.pending {
+                self.pending = automaton.output[automaton.fail[node]];
+                let found = &automaton.trie.nodes[node];
+                return Some(Match { pattern: found.pattern.unwrap(), start: self.pos - found.depth, end: self.pos });
+            }
+            let &byte = self.text.get(self.pos)?;
+            self.node = automaton.step(self.node, byte);
+            self.pos += 1;
+            self.pending = automaton.output[self.node];
+        }
+    }
+}
+
+/// Iterator returned by `AhoCorasick::segmentations`
+pub struct Segmentations {
+    /// Completable matches grouped by start position
+    by_start: Vec<Vec<Match>>,
+    len: usize,
+    path: Vec<Match>,
+    /// Index of each match of `path` within its `by_start` group
+    choices: Vec<usize>,
+    done: bool,
+}
+
+impl Segmentations {
+    /// Replace the last match that has an untried alternative, dropping everything after it
+    fn advance(&mut self) {
+        while let (Some(m), Some(choice)) = (self.path.pop(), self.choices.pop()) {
+            if let Some(&next) = self.by_start[m.start].get(choice + 1) {
+                self.path.push(next);
+                self.choices.push(choice + 1);
+                return;
+            }
+        }
+        self.done = true;
+    }
+}
+
+impl Iterator for Segmentations {
+    type Item = Vec<Match>;
+
+    fn next(&mut self) -> Option<Vec<Match>> {
+        if self.done {
+            return None;
+        }
+        loop {
+            let pos = self.path.last().map_or(0, |m| m.end);
+            if pos == self.len {
+                let found = self.path.clone();
+                self.advance();
+                return Some(found);
+            }
+            // only the first position can be a dead end, when there are no splits at all
+            let Some(&first) = self.by_start[pos].first() else {
+                self.done = true;
+                return None;
+            };
+            self.path.push(first);
+            self.choices.push(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOWELS: [&str; 8] = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+
+    #[test]
+    fn test_trie() {
+        let mut trie = Trie::new();
+        for towel in TOWELS {
+            trie.insert(towel);
+        }
+        assert_eq!(trie.insert("gb"), 6);
+        assert_eq!(trie.len(), 8);
+        assert!(trie.contains(b"bwu") && trie.contains(b"r"));
+        assert!(!trie.contains(b"bw") && !trie.contains(b"") && !trie.contains(b"rr"));
+        assert_eq!(trie.find(b"br"), Some(7));
+
+        let prefixes: Vec<_> = trie.prefixes(b"bwurrg").collect();
+        assert_eq!(prefixes, [(2, 1), (4, 3)]);
+        assert_eq!(trie.prefixes(b"xbr").count(), 0);
+    }
+
+    #[test]
+    fn test_find_overlapping() {
+        let automaton = AhoCorasick::new(["he", "she", "his", "hers"]);
+        let found: Vec<(&str, usize)> = automaton
+            .find_overlapping(b"ushers")
+            .map(|m| (["he", "she", "his", "hers"][m.pattern], m.start))
+            .collect();
+        assert_eq!(found, [("she", 1), ("he", 2), ("hers", 2)]);
+
+        let nested = AhoCorasick::new(["a", "aa", "aaa"]);
+        assert_eq!(nested.find_overlapping(b"aaaa").count(), 4 + 3 + 2);
+        assert!(nested.find_overlapping(b"b").next().is_none());
+    }
+
+    #[test]
+    fn test_segmentation() {
+        let automaton = AhoCorasick::new(TOWELS);
+        let designs = ["brwrr", "bggr", "gbbr", "rrbgbr", "ubwu", "bwurrg", "brgr", "bbrgwb"];
+        let counts: Vec<u64> = designs.iter().map(|design| automaton.count_segmentations(design.as_bytes())).collect();
+        assert_eq!(counts, [2, 1, 4, 6, 0, 1, 2, 0]);
+        for (design, count) in designs.iter().zip(counts) {
+            assert_eq!(automaton.can_segment(design.as_bytes()), count > 0);
+        }
+        assert_eq!(automaton.count_segmentations(b""), 1);
+    }
+
+    fn pieces<'a>(text: &'a str, matches: &[Match]) -> Vec<&'a str> {
+        matches.iter().map(|m| &text[m.start..m.end]).collect()
+    }
+
+    #[test]
+    fn test_segmentations() {
+        let automaton = AhoCorasick::new(TOWELS);
+        let design = "rrbgbr";
+        let all: Vec<Vec<&str>> = automaton.segmentations(design.as_bytes()).map(|s| pieces(design, &s)).collect();
+        assert_eq!(all.len(), 6);
+        assert!(all.iter().all(|split| split.concat() == design));
+        assert!(all.contains(&vec!["r", "rb", "gb", "r"]));
+        assert!(all.contains(&vec!["r", "r", "b", "g", "b", "r"]));
+        let mut unique = all.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), all.len());
+
+        let one = automaton.segmentation(design.as_bytes()).unwrap();
+        assert!(all.contains(&pieces(design, &one)));
+        let shortest = automaton.shortest_segmentation(design.as_bytes()).unwrap();
+        assert_eq!(shortest.len(), all.iter().map(|split| split.len()).min().unwrap());
+        assert_eq!(pieces("bwurrg", &automaton.shortest_segmentation(b"bwurrg").unwrap()), ["bwu", "r", "r", "g"]);
+
+        assert!(automaton.segmentation(b"ubwu").is_none());
+        assert!(automaton.shortest_segmentation(b"ubwu").is_none());
+        assert_eq!(automaton.segmentations(b"ubwu").count(), 0);
+        assert_eq!(automaton.segmentations(b"").collect::<Vec<_>>(), [Vec::<Match>::new()]);
+    }
+
+    #[test]
+    fn test_segmentations_are_lazy() {
+        // about 2.5 trillion ways to split, so only the first few can be looked at
+        let automaton = AhoCorasick::new(["a", "aa"]);
+        let text = [b'a'; 60];
+        let first: Vec<Vec<Match>> = automaton.segmentations(&text).take(3).collect();
+        assert_eq!(first[0].len(), 60);
+        assert_eq!(first[1].last().unwrap().start, 58);
+    }
+}
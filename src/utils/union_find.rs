@@ -0,0 +1,189 @@
+use super::grid::{Grid, Point};
+
+/// Disjoint sets over `0..len` with path compression and union by rank
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /// `len` singleton sets
+    pub fn new(len: usize) -> Self {
+        Self { parent: (0..len).collect(), rank: vec![0; len], size: vec![1; len], sets: len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Add a new singleton set, returning its element
+    pub fn add(&mut self) -> usize {
+        let id = self.parent.len();
+        self.parent.push(id);
+        self.rank.push(0);
+        self.size.push(1);
+        self.sets += 1;
+        id
+    }
+
+    /// Representative of the set containing `x`
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = x;
+        while self.parent[current] != root {
+            current = std::mem::replace(&mut self.parent[current], root);
+        }
+        root
+    }
+
+    /// Merge the sets containing `a` and `b`, returning `false` if they were already one set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set containing `x`
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+}
+
+/// Index of the first obstacle in `obstacles` whose placement disconnects `a` from `b`
+/// on a `width` x `height` grid with 4-connected moves, or `None` if they stay connected
+/// (or were never connected). Cells where `is_wall` holds are always blocked.
+///
+/// Works offline in reverse: block every obstacle, then remove them from last to first,
+/// merging each freed cell with its free neighbors until `a` and `b` join.
+pub fn first_disconnecting(
+    width: usize,
+    height: usize,
+    is_wall: impl Fn(Point) -> bool,
+    obstacles: &[Point],
+    a: Point,
+    b: Point,
+) -> Option<usize> {
+    // a cell stays blocked until its earliest obstacle is removed
+    let mut blocked_from = Grid::new(width, height, None);
+    for (i, &p) in obstacles.iter().enumerate().rev() {
+        blocked_from[p] = Some(i);
+    }
+    let index = |p: Point| p.y as usize * width + p.x as usize;
+    let mut free = Grid::new(width, height, false);
+    let mut sets = UnionFind::new(width * height);
+
+    let open = |p: Point, free: &mut Grid<bool>, sets: &mut UnionFind| {
+        free[p] = true;
+        for next in free.neighbors(p).filter(|&next| free[next]).collect::<Vec<_>>() {
+            sets.union(index(p), index(next));
+        }
+    };
+    let joined = |free: &Grid<bool>, sets: &mut UnionFind| {
+        free[a] && free[b] && sets.connected(index(a), index(b))
+    };
+
+    for p in free.positions().collect::<Vec<_>>() {
+        if blocked_from[p].is_none() && !is_wall(p) {
+            open(p, &mut free, &mut sets);
+        }
+    }
+    if joined(&free, &mut sets) {
+        return None;
+    }
+    for (i, &p) in obstacles.iter().enumerate().rev() {
+        if blocked_from[p] == Some(i) && !is_wall(p) {
+            open(p, &mut free, &mut sets);
+            if joined(&free, &mut sets) {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.set_count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.set_size(5), 1);
+        assert_eq!(sets.set_count(), 3);
+
+        let new = sets.add();
+        assert_eq!((new, sets.len(), sets.set_count()), (6, 7, 4));
+        sets.union(new, 5);
+        assert!(sets.connected(5, 6));
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let mut sets = UnionFind::new(100_000);
+        for i in 1..sets.len() {
+            sets.union(i - 1, i);
+        }
+        assert_eq!(sets.set_count(), 1);
+        assert_eq!(sets.set_size(0), 100_000);
+        assert_eq!(sets.find(99_999), sets.find(0));
+    }
+
+    #[test]
+    fn test_first_disconnecting() {
+        let (a, b) = (Point::new(0, 0), Point::new(2, 2));
+        // wall down the middle column, closed from the bottom up
+        let obstacles = [Point::new(1, 2), Point::new(0, 1), Point::new(1, 1), Point::new(1, 0), Point::new(2, 0)];
+        assert_eq!(first_disconnecting(3, 3, |_| false, &obstacles, a, b), Some(3));
+        assert_eq!(first_disconnecting(3, 3, |_| false, &obstacles[..3], a, b), None);
+
+        // a repeated obstacle only counts the first time
+        let repeated = [Point::new(1, 2), Point::new(1, 2), Point::new(1, 1), Point::new(1, 0)];
+        assert_eq!(first_disconnecting(3, 3, |_| false, &repeated, a, b), Some(3));
+
+        // blocking an endpoint disconnects it
+        assert_eq!(first_disconnecting(3, 3, |_| false, &[Point::new(2, 1), b], a, b), Some(1));
+        // with walls already in place
+        let wall = |p: Point| p.x == 1 && p.y > 0;
+        assert_eq!(first_disconnecting(3, 3, wall, &[Point::new(0, 2), Point::new(1, 0)], a, b), Some(1));
+        assert_eq!(first_disconnecting(3, 3, |p| p.x == 1, &[Point::new(2, 1)], a, b), None);
+    }
+}
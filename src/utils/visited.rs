@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use super::grid::{Direction, Grid, Point};
+
+/// Set of already explored search states
+pub trait VisitedSet<T> {
+    /// Mark `item` as visited, returning `true` if it was not visited before
+    fn insert(&mut self, item: T) -> bool;
+
+    fn contains(&self, item: &T) -> bool;
+}
+
+impl<T: Hash + Eq> VisitedSet<T> for HashSet<T> {
+    fn insert(&mut self, item: T) -> bool {
+        HashSet::insert(self, item)
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        HashSet::contains(self, item)
+    }
+}
+
+/// One bit per grid cell, optionally with a separate bit for each `Direction`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    planes: usize,
+}
+
+impl BitGrid {
+    /// Empty set of cells for a `width` x `height` grid
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_planes(width, height, 1)
+    }
+
+    /// Empty set of `(cell, direction)` states for a `width` x `height` grid
+    pub fn with_directions(width: usize, height: usize) -> Self {
+        Self::with_planes(width, height, 1 + Direction::ALL.len())
+    }
+
+    /// Empty set of cells with the same size as `grid`
+    pub fn for_grid<T>(grid: &Grid<T>) -> Self {
+        Self::new(grid.width(), grid.height())
+    }
+
+    fn with_planes(width: usize, height: usize, planes: usize) -> Self {
+        let bits = width * height * planes;
+        Self { words: vec![0; bits.div_ceil(64)], width, height, planes }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn bit(&self, p: Point, plane: usize) -> Option<usize> {
+        let in_bounds = p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height;
+        in_bounds.then(|| (p.y as usize * self.width + p.x as usize) * self.planes + plane)
+    }
+
+    fn test(&self, p: Point, plane: usize) -> bool {
+        self.bit(p, plane).is_some_and(|bit| self.words[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    fn set(&mut self, p: Point, plane: usize) -> bool {
+        let bit = self.bit(p, plane).unwrap_or_else(|| panic!("{:?} is outside the bit grid", p));
+        let word = &mut self.words[bit / 64];
+        let mask = 1 << (bit % 64);
+        let new = *word & mask == 0;
+        *word |= mask;
+        new
+    }
+
+    fn direction_plane(&self, dir: Direction) -> usize {
+        assert!(self.planes > 1, "bit grid was created without direction planes");
+        1 + dir as usize
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.test(p, 0)
+    }
+
+    /// Mark a cell, returning `true` if it was not marked before
+    pub fn insert(&mut self, p: Point) -> bool {
+        self.set(p, 0)
+    }
+
+    pub fn remove(&mut self, p: Point) {
+        if let Some(bit) = self.bit(p, 0) {
+            self.words[bit / 64] &= !(1 << (bit % 64));
+        }
+    }
+
+    pub fn contains_dir(&self, p: Point, dir: Direction) -> bool {
+        self.test(p, self.direction_plane(dir))
+    }
+
+    /// Mark a cell as entered facing `dir`, returning `true` if it was not marked before
+    pub fn insert_dir(&mut self, p: Point, dir: Direction) -> bool {
+        let plane = self.direction_plane(dir);
+        self.set(p, plane)
+    }
+
+    /// Number of marked cells, ignoring direction bits
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Marked cells, row by row
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        let width = self.width;
+        (0..self.width * self.height)
+            .map(move |i| Point::new((i % width) as i32, (i / width) as i32))
+            .filter(|&p| self.contains(p))
+    }
+}
+
+impl VisitedSet<Point> for BitGrid {
+    fn insert(&mut self, p: Point) -> bool {
+        BitGrid::insert(self, p)
+    }
+
+    fn contains(&self, p: &Point) -> bool {
+        BitGrid::contains(self, *p)
+    }
+}
+
+impl VisitedSet<(Point, Direction)> for BitGrid {
+    fn insert(&mut self, (p, dir): (Point, Direction)) -> bool {
+        self.insert_dir(p, dir)
+    }
+
+    fn contains(&self, (p, dir): &(Point, Direction)) -> bool {
+        self.contains_dir(*p, *dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_grid() {
+        let mut bits = BitGrid::new(9, 9);
+        assert!(bits.is_empty());
+        assert!(bits.insert(Point::new(8, 8)));
+        assert!(!bits.insert(Point::new(8, 8)));
+        assert!(bits.insert(Point::new(0, 7)));
+        assert!(bits.contains(Point::new(8, 8)));
+        assert!(!bits.contains(Point::new(7, 8)));
+        assert!(!bits.contains(Point::new(-1, 0)));
+        assert_eq!(bits.iter().collect::<Vec<_>>(), vec![Point::new(0, 7), Point::new(8, 8)]);
+
+        bits.remove(Point::new(8, 8));
+        assert_eq!(bits.len(), 1);
+        bits.clear();
+        assert!(bits.is_empty());
+    }
+
+    #[test]
+    fn test_direction_planes() {
+        let mut bits = BitGrid::with_directions(3, 2);
+        let p = Point::new(2, 1);
+        assert!(bits.insert_dir(p, Direction::West));
+        assert!(!bits.insert_dir(p, Direction::West));
+        assert!(bits.insert_dir(p, Direction::North));
+        assert!(bits.contains_dir(p, Direction::North));
+        assert!(!bits.contains_dir(p, Direction::South));
+        assert!(!bits.contains(p));
+        assert_eq!(bits.len(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_insert_out_of_bounds() {
+        BitGrid::new(2, 2).insert(Point::new(2, 0));
+    }
+
+    fn visit_all<V: VisitedSet<Point>>(mut visited: V, points: &[Point]) -> usize {
+        points.iter().filter(|&&p| visited.insert(p)).count()
+    }
+
+    #[test]
+    fn test_interchangeable_with_hash_set() {
+        let points = [Point::new(1, 1), Point::new(0, 1), Point::new(1, 1)];
+        assert_eq!(visit_all(HashSet::new(), &points), 2);
+        assert_eq!(visit_all(BitGrid::new(2, 2), &points), 2);
+
+        let mut states = BitGrid::with_directions(2, 2);
+        assert!(VisitedSet::insert(&mut states, (Point::new(0, 0), Direction::East)));
+        assert!(VisitedSet::contains(&states, &(Point::new(0, 0), Direction::East)));
+    }
+}
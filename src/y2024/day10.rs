@@ -1,81 +1,57 @@
-use std::collections::HashSet;
+use crate::utils::dag::{count_paths, count_reachable_targets};
+use crate::utils::grid::{Grid, Point};
 
-fn count_paths(grid: &Vec<Vec<u32>>, start: (usize, usize), track_visited: bool) -> u32 {
-    let mut stack = vec![start];
-    let mut visited = if track_visited { HashSet::from([start]) } else { HashSet::new() };
-    let mut count = 0;
-
-    let directions = [(0, 1), (1, 0), (0, -1), (-1, 0)];
-
-    while let Some((y, x)) = stack.pop() {
-        if grid[y][x] == 9 {
-            count += 1;
-            continue;
-        }
+fn parse_input(input: &str) -> Grid<u32> {
+    Grid::parse(input.trim(), |c| c.to_digit(10).unwrap())
+}
 
-        for (dy, dx) in directions {
-            let new_y = y as i32 + dy;
-            let new_x = x as i32 + dx;
+fn trailheads(grid: &Grid<u32>) -> impl Iterator<Item = Point> + '_ {
+    grid.iter().filter(|&(_, &height)| height == 0).map(|(p, _)| p)
+}
 
-            if new_y >= 0 && new_y < grid.len() as i32 
-               && new_x >= 0 && new_x < grid[0].len() as i32 {
-                let new_y = new_y as usize;
-                let new_x = new_x as usize;
-                
-                if grid[new_y][new_x] == grid[y][x] + 1 
-                   && (!track_visited || !visited.contains(&(new_y, new_x))) {
-                    stack.push((new_y, new_x));
-                    if track_visited {
-                        visited.insert((new_y, new_x));
-                    }
-                }
-            }
-        }
-    }
+/// Trails climb exactly one height per step, so they can never loop
+fn uphill(grid: &Grid<u32>) -> impl Fn(&Point) -> Vec<Point> + '_ {
+    |&p| grid.neighbors(p).filter(|&next| grid[next] == grid[p] + 1).collect()
+}
 
-    count
+fn is_summit(grid: &Grid<u32>) -> impl Fn(&Point) -> bool + '_ {
+    |&p| grid[p] == 9
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let grid: Vec<Vec<u32>> = input
-        .lines()
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect();
-
-    let mut result = 0;
-    for y in 0..grid.len() {
-        for x in 0..grid[0].len() {
-            if grid[y][x] == 0 {
-                result += count_paths(&grid, (y, x), true);
-            }
-        }
-    }
-
-    Some(result)
+    let grid = parse_input(input);
+    let score = trailheads(&grid)
+        .map(|start| count_reachable_targets(start, is_summit(&grid), uphill(&grid)))
+        .sum::<usize>();
+    Some(score as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let grid: Vec<Vec<u32>> = input
-        .lines()
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect();
-
-    let mut result = 0;
-    for y in 0..grid.len() {
-        for x in 0..grid[0].len() {
-            if grid[y][x] == 0 {
-                result += count_paths(&grid, (y, x), false);
-            }
-        }
-    }
-
-    Some(result)
+    let grid = parse_input(input);
+    let rating = count_paths(trailheads(&grid), is_summit(&grid), uphill(&grid));
+    Some(rating as u32)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part_one(EXAMPLE), Some(36));
+        assert_eq!(part_two(EXAMPLE), Some(81));
+    }
+
     #[test]
     fn test_part_one() {
         let input = std::fs::read_to_string("input/day10.txt").unwrap();
@@ -1,42 +1,49 @@
-use std::collections::HashMap;
-
-fn blink(stones: &HashMap<u64, u64>) -> HashMap<u64, u64> {
-    let mut new = HashMap::with_capacity(stones.len() * 2);
-    for (&stone, &amount) in stones {
-        if stone == 0 {
-            *new.entry(1).or_default() += amount;
-            continue;
-        }
-        
-        let digits = stone.ilog10() + 1;
-        if digits % 2 == 0 {
-            let magnitude = 10u64.pow(digits / 2);
-            let lower = stone % magnitude;
-            let upper = stone / magnitude;
-            *new.entry(lower).or_default() += amount;
-            *new.entry(upper).or_default() += amount;
-        } else {
-            *new.entry(stone * 2024).or_default() += amount;
-        }
+use crate::utils::memo::Memo;
+
+/// The stone or pair of stones that `stone` turns into after one blink
+fn blink(stone: u64) -> (u64, Option<u64>) {
+    if stone == 0 {
+        return (1, None);
     }
-    new
-}
 
-fn solve(input: &str, times: u8) -> usize {
-    let mut stones = HashMap::new();
-    for n in input.split_ascii_whitespace().map(|s| s.parse::<u64>().unwrap()) {
-        *stones.entry(n).or_default() += 1;
+    let digits = stone.ilog10() + 1;
+    if digits.is_multiple_of(2) {
+        let magnitude = 10u64.pow(digits / 2);
+        (stone / magnitude, Some(stone % magnitude))
+    } else {
+        (stone * 2024, None)
     }
-    for _ in 0..times {
-        stones = blink(&stones);
+}
+
+/// Number of stones `stone` turns into after `blinks` blinks
+fn count_stones(stone: u64, blinks: u8, memo: &mut Memo<(u64, u8), u64>) -> u64 {
+    if blinks == 0 {
+        return 1;
     }
-    stones.values().sum::<u64>() as usize
+    memo.get_or_compute(&(stone, blinks), |memo| {
+        let (left, right) = blink(stone);
+        count_stones(left, blinks - 1, memo) + right.map_or(0, |right| count_stones(right, blinks - 1, memo))
+    })
+}
+
+fn solve(input: &str, times: u8) -> usize {
+    let mut memo = Memo::new();
+    input
+        .split_ascii_whitespace()
+        .map(|s| count_stones(s.parse::<u64>().unwrap(), times, &mut memo))
+        .sum::<u64>() as usize
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_example() {
+        assert_eq!(solve("125 17", 6), 22);
+        assert_eq!(solve("125 17", 25), 55312);
+    }
+
     #[test]
     fn test_part_1() {
         let input = std::fs::read_to_string("input/day11.txt").unwrap();
@@ -1,3 +1,5 @@
+use crate::utils::linear::LinearSystem;
+
 const COST_MOVE_A: usize = 3;
 const COST_MOVE_B: usize = 1;
 
@@ -14,30 +16,24 @@ pub struct Position {
 }
 
 impl Machine {
+    /// Cheapest button presses reaching the prize, also when the buttons move in the same
+    /// direction and several press counts reach it
     fn min_cost_to_win(&self, target_offset: isize) -> Option<usize> {
         let target_x = self.target.x + target_offset;
         let target_y = self.target.y + target_offset;
-        
-        // Calculate coefficients for the linear system
-        let numerator = self.move_b.x * target_y - self.move_b.y * target_x;
-        let denominator = self.move_a.y * self.move_b.x - self.move_a.x * self.move_b.y;
-        
-        // Check if solution exists
-        if numerator % denominator != 0 {
-            return None;
-        }
-
-        let moves_a = numerator / denominator;
-        let remainder_x = target_x - (moves_a * self.move_a.x);
-        
-        if remainder_x % self.move_b.x != 0 {
-            return None;
-        }
-
-        let moves_b = remainder_x / self.move_b.x;
-        debug_assert_eq!(target_y, moves_a * self.move_a.y + moves_b * self.move_b.y);
-        
-        Some(moves_a as usize * COST_MOVE_A + moves_b as usize * COST_MOVE_B)
+
+        let system = LinearSystem::new(
+            &[
+                vec![self.move_a.x as i64, self.move_b.x as i64],
+                vec![self.move_a.y as i64, self.move_b.y as i64],
+            ],
+            &[target_x as i64, target_y as i64],
+        );
+        let presses = system
+            .min_cost_nonnegative(&[COST_MOVE_A as i64, COST_MOVE_B as i64])
+            .expect("two buttons leave at most one free variable")?;
+
+        Some(presses[0] as usize * COST_MOVE_A + presses[1] as usize * COST_MOVE_B)
     }
 }
 
@@ -87,6 +83,50 @@ mod tests {
 
     use super::*;
 
+    const EXAMPLE: &str = "\
+Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279";
+
+    #[test]
+    fn test_example() {
+        let machines = parse(EXAMPLE);
+        assert_eq!(part_1(&machines), 480);
+        assert_eq!(part_2(&machines), 875318608908);
+    }
+
+    #[test]
+    fn test_degenerate_machines() {
+        let machine = |a: (isize, isize), b: (isize, isize), target: (isize, isize)| Machine {
+            move_a: Position { x: a.0, y: a.1 },
+            move_b: Position { x: b.0, y: b.1 },
+            target: Position { x: target.0, y: target.1 },
+        };
+        // both buttons move diagonally: B alone is cheapest
+        assert_eq!(machine((2, 2), (1, 1), (4, 4)).min_cost_to_win(0), Some(4));
+        // A is worth more than three presses of B, so it's used as often as possible
+        assert_eq!(machine((4, 4), (1, 1), (9, 9)).min_cost_to_win(0), Some(7));
+        // only a mix of both reaches the prize
+        assert_eq!(machine((4, 6), (6, 9), (26, 39)).min_cost_to_win(0), Some(9));
+        assert_eq!(machine((2, 2), (4, 4), (3, 3)).min_cost_to_win(0), None);
+        assert_eq!(machine((2, 2), (1, 1), (4, 5)).min_cost_to_win(0), None);
+        // a button that doesn't move the claw is never worth pressing
+        assert_eq!(machine((0, 0), (1, 2), (3, 6)).min_cost_to_win(0), Some(3));
+        assert_eq!(machine((0, 0), (0, 0), (0, 0)).min_cost_to_win(0), Some(0));
+    }
+
     #[test]
     fn test_part_1() {
         let input = fs::read_to_string("input/day13.txt").unwrap();
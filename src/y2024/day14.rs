@@ -1,25 +1,14 @@
+use crate::utils::grid::{Point, Torus, Vector};
+use crate::utils::math::crt;
+
 #[derive(Debug, Clone, Copy)]
-struct Robot<const WIDTH: usize, const HEIGHT: usize> {
-    x: usize,
-    y: usize,
-    dx: isize,
-    dy: isize,
+struct Robot {
+    pos: Point,
+    vel: Vector,
 }
 
-impl<const WIDTH: usize, const HEIGHT: usize> Robot<WIDTH, HEIGHT> {
-    fn update_position_in_seconds(&mut self, seconds: isize) {
-        self.x =
-            ((self.x + WIDTH) as isize + ((self.dx * seconds) % WIDTH as isize)) as usize % WIDTH;
-        self.y = ((self.y + HEIGHT) as isize + ((self.dy * seconds) % HEIGHT as isize)) as usize
-            % HEIGHT;
-    }
-
-    fn update_position(&mut self) {
-        self.x = ((self.x + WIDTH) as isize + self.dx) as usize % WIDTH;
-        self.y = ((self.y + HEIGHT) as isize + self.dy) as usize % HEIGHT;
-    }
-
-    fn parse(input: &str) -> Vec<Robot<WIDTH, HEIGHT>> {
+impl Robot {
+    fn parse(input: &str) -> Vec<Robot> {
         input
             .lines()
             .filter_map(|line| {
@@ -29,22 +18,23 @@ impl<const WIDTH: usize, const HEIGHT: usize> Robot<WIDTH, HEIGHT> {
                 let mut pos_iter = pos.trim_start_matches("p=").split(',');
                 let mut vel_iter = vel.trim_start_matches("v=").split(',');
 
-                let x = pos_iter.next()?.parse::<usize>().unwrap();
-                let y = pos_iter.next()?.parse::<usize>().unwrap();
-                let dx = vel_iter.next()?.parse::<isize>().unwrap() % WIDTH as isize;
-                let dy = vel_iter.next()?.parse::<isize>().unwrap() % HEIGHT as isize;
+                let x = pos_iter.next()?.parse::<i32>().unwrap();
+                let y = pos_iter.next()?.parse::<i32>().unwrap();
+                let dx = vel_iter.next()?.parse::<i32>().unwrap();
+                let dy = vel_iter.next()?.parse::<i32>().unwrap();
 
-                Some(Robot { x, y, dx, dy })
+                Some(Robot { pos: Point::new(x, y), vel: Vector::new(dx, dy) })
             })
             .collect()
     }
 
-    pub fn quadrant(&self) -> Option<u8> {
+    pub fn quadrant(&self, area: &Torus) -> Option<u8> {
+        let (mid_x, mid_y) = (area.width / 2, area.height / 2);
         match (
-            self.x < WIDTH / 2,
-            self.x > WIDTH / 2,
-            self.y < HEIGHT / 2,
-            self.y > HEIGHT / 2,
+            self.pos.x < mid_x,
+            self.pos.x > mid_x,
+            self.pos.y < mid_y,
+            self.pos.y > mid_y,
         ) {
             (false, false, _, _) => None,
             (_, _, false, false) => None,
@@ -53,12 +43,10 @@ impl<const WIDTH: usize, const HEIGHT: usize> Robot<WIDTH, HEIGHT> {
     }
 }
 
-fn safety_factor_for_quadrants<const WIDTH: usize, const HEIGHT: usize>(
-    robots: Vec<Robot<WIDTH, HEIGHT>>,
-) -> (i32, i32, i32, i32) {
+fn safety_factor_for_quadrants(robots: &[Robot], area: &Torus) -> (i32, i32, i32, i32) {
     let mut quadrant_counts = (0, 0, 0, 0);
-    for robot in &robots {
-        if let Some(quadrant) = robot.quadrant() {
+    for robot in robots {
+        if let Some(quadrant) = robot.quadrant(area) {
             match quadrant {
                 0 => quadrant_counts.0 += 1,
                 1 => quadrant_counts.1 += 1,
@@ -71,61 +59,113 @@ fn safety_factor_for_quadrants<const WIDTH: usize, const HEIGHT: usize>(
     quadrant_counts
 }
 
-fn part_1(input: &str) -> i32 {
-    let mut robots = Robot::<101, 103>::parse(input);
+fn safety_factor(input: &str, area: &Torus, seconds: i64) -> i32 {
+    let mut robots = Robot::parse(input);
     for robot in &mut robots {
-        robot.update_position_in_seconds(100);
+        robot.pos = area.advance(robot.pos, robot.vel, seconds);
     }
 
-    let quadrant_counts = safety_factor_for_quadrants(robots);
+    let quadrant_counts = safety_factor_for_quadrants(&robots, area);
     quadrant_counts.0 * quadrant_counts.1 * quadrant_counts.2 * quadrant_counts.3
 }
 
-fn standard_deviation(data: &[usize]) -> f32 {
-    let sum = data.iter().sum::<usize>() as f32;
-    let count = data.len() as f32;
-    let mean = sum / count;
-    let variance = data
-        .iter()
+fn part_1(input: &str) -> i32 {
+    safety_factor(input, &Torus::new(101, 103), 100)
+}
+
+fn variance(data: &[i32]) -> f64 {
+    let count = data.len() as f64;
+    let mean = data.iter().map(|&value| value as f64).sum::<f64>() / count;
+    data.iter()
         .map(|&value| {
-            let distance = mean - value as f32;
+            let distance = mean - value as f64;
             distance * distance
         })
-        .sum::<f32>()
-        / count;
-
-    variance.sqrt()
+        .sum::<f64>()
+        / count
 }
 
-fn find_picture_of_tree<const WIDTH: usize, const HEIGHT: usize>(
-    robots: &mut [Robot<WIDTH, HEIGHT>],
-) -> usize {
-    let mut seconds = 0;
-    loop {
-        robots.iter_mut().for_each(|robot| robot.update_position());
-        seconds += 1;
-
-        let (xs, ys): (Vec<usize>, Vec<usize>) =
-            robots.iter().map(|robot| (robot.x, robot.y)).unzip();
-
-        let x_score = standard_deviation(&xs);
-        let y_score = standard_deviation(&ys);
+/// Second in `0..period` at which the robots' coordinates along one axis are least spread out.
+/// Each axis repeats with the torus size along it, so only one period needs checking.
+fn tightest_second(robots: &[Robot], area: &Torus, period: i32, axis: fn(Point) -> i32) -> i64 {
+    (0..period as i64)
+        .map(|seconds| {
+            let coordinates: Vec<i32> =
+                robots.iter().map(|robot| axis(area.advance(robot.pos, robot.vel, seconds))).collect();
+            (variance(&coordinates), seconds)
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap()
+        .1
+}
 
-        if x_score < 25.0 && y_score < 25.0 {
-            return seconds;
-        }
-    }
+/// The robots form the picture when both axes are at their tightest, which happens at the same
+/// second at most once per `lcm(width, height)` seconds. `None` if the tightest seconds of the two
+/// axes never coincide, which can only happen when the sides share a factor.
+fn find_picture_of_tree(robots: &[Robot], area: &Torus) -> Option<usize> {
+    let x_seconds = tightest_second(robots, area, area.width, |p| p.x);
+    let y_seconds = tightest_second(robots, area, area.height, |p| p.y);
+    let (seconds, _) = crt(&[(x_seconds, area.width as i64), (y_seconds, area.height as i64)])?;
+    Some(seconds as usize)
 }
 
 fn part2(input: &str) -> usize {
-    let mut robots = Robot::<101, 103>::parse(input);
-    find_picture_of_tree(&mut robots)
+    let robots = Robot::parse(input);
+    find_picture_of_tree(&robots, &Torus::new(101, 103)).expect("The axes are never tightest at the same second")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+    #[test]
+    fn example_test() {
+        assert_eq!(safety_factor(EXAMPLE, &Torus::new(11, 7), 100), 12);
+    }
+
+    #[test]
+    fn test_find_picture() {
+        // robots that all end up in a 10x10 square after 6876 seconds, scattered otherwise
+        let area = Torus::new(101, 103);
+        let mut seed = 7u32;
+        let mut random = |n: i32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as i32 % n
+        };
+        let robots: Vec<Robot> = (0..300)
+            .map(|_| {
+                let vel = Vector::new(random(201) - 100, random(205) - 102);
+                let target = Point::new(40 + random(10), 40 + random(10));
+                Robot { pos: area.advance(target, -vel, 6876), vel }
+            })
+            .collect();
+        assert_eq!(find_picture_of_tree(&robots, &area), Some(6876));
+    }
+
+    #[test]
+    fn test_axes_never_tightest_together() {
+        // x lines up at multiples of 4 seconds and y one second after multiples of 6, never together
+        let area = Torus::new(4, 6);
+        let robots: Vec<Robot> = (1..=3)
+            .map(|k| Robot { pos: Point::new(0, 6 - k), vel: Vector::new(k, k) })
+            .collect();
+        assert_eq!(find_picture_of_tree(&robots, &area), None);
+    }
+
     #[test]
     fn part1_test() {
         let input = std::fs::read_to_string("input/day14.txt").unwrap();
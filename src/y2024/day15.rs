@@ -1,10 +1,4 @@
-use std::isize;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: usize,
-    y: usize,
-}
+use crate::utils::grid::{Direction, Grid, Point, Vector};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Tile {
@@ -17,101 +11,67 @@ enum Tile {
 }
 
 struct Warehouse {
-    width: usize,
-    height: usize,
-    robot: Position,
-    grid: Vec<Vec<Tile>>,
+    robot: Point,
+    grid: Grid<Tile>,
 }
 
 impl Warehouse {
     fn new(map: &str) -> Self {
-        let mut robot = Position { x: 0, y: 0 };
-        let mut grid = Vec::new();
-        let mut width = 0;
-        let mut height = 0;
-
-        for (y, line) in map.lines().enumerate() {
-            height += 1;
-            width = line.len();
-            let mut row = Vec::new();
-            for (x, ch) in line.chars().enumerate() {
-                let pos = Position { x, y };
-                let tile = match ch {
-                    '@' => {
-                        robot = pos;
-                        Tile::Robot
-                    }
-                    'O' => Tile::Object,
-                    '#' => Tile::Wall,
-                    _ => Tile::Empty,
-                };
-                row.push(tile);
-            }
-            grid.push(row);
-        }
-
-        Warehouse {
-            width,
-            height,
-            robot,
-            grid,
-        }
+        let grid = Grid::parse(map, |ch| match ch {
+            '@' => Tile::Robot,
+            'O' => Tile::Object,
+            '#' => Tile::Wall,
+            _ => Tile::Empty,
+        });
+        let robot = grid.find(|&tile| tile == Tile::Robot).unwrap_or(Point::new(0, 0));
+
+        Warehouse { robot, grid }
     }
 
     fn move_robot(&mut self, direction: char) {
-        let (dx, dy) = match direction {
-            '^' => (0, -1 as isize),
-            'v' => (0, 1),
-            '<' => (-1 as isize, 0),
-            '>' => (1, 0),
-            _ => (0, 0),
-        };
-        let new_pos = Position {
-            x: (self.robot.x as isize + dx) as usize,
-            y: (self.robot.y as isize + dy) as usize,
+        let Some(direction) = Direction::from_char(direction) else {
+            return;
         };
+        let Vector { dx, dy } = direction.vector();
+        let new_pos = self.robot + direction.vector();
         if self.can_move(new_pos, dx, dy) {
             self.move_tile(self.robot, dx, dy);
             self.robot = new_pos;
         }
     }
 
-    fn can_move(&self, new_pos: Position, dx: isize, dy: isize) -> bool {
-        let next_pos = Position {
-            x: (new_pos.x as isize + dx) as usize,
-            y: (new_pos.y as isize + dy) as usize,
-        };
-        match self.grid[new_pos.y][new_pos.x] {
+    fn can_move(&self, new_pos: Point, dx: i32, dy: i32) -> bool {
+        let next_pos = new_pos + Vector::new(dx, dy);
+        match self.grid[new_pos] {
             Tile::Wall => false,
             Tile::Object => self.can_move(next_pos, dx, dy),
             Tile::LeftBox => {
                 if dx == -1 {
                     self.can_move(new_pos, dx, dy)
                 } else if dx == 1 {
-                    self.can_move(Position { x: next_pos.x + 1, y: next_pos.y }, dx, dy)
+                    self.can_move(Point::new(next_pos.x + 1, next_pos.y), dx, dy)
                 } else {
-                    self.can_move(next_pos, dx, dy) && self.can_move(Position { x: next_pos.x + 1, y: next_pos.y }, dx, dy)
+                    self.can_move(next_pos, dx, dy) && self.can_move(Point::new(next_pos.x + 1, next_pos.y), dx, dy)
                 }
             }
             Tile::RightBox => {
                 if dx == -1 {
-                    self.can_move(Position { x: next_pos.x - 1, y: next_pos.y }, dx, dy)
+                    self.can_move(Point::new(next_pos.x - 1, next_pos.y), dx, dy)
                 } else if dx == 1 {
                     self.can_move(next_pos, dx, dy)
                 } else {
-                    self.can_move(Position { x: next_pos.x - 1, y: next_pos.y }, dx, dy) && self.can_move(next_pos, dx, dy)
+                    self.can_move(Point::new(next_pos.x - 1, next_pos.y), dx, dy) && self.can_move(next_pos, dx, dy)
                 }
             }
             _ => true,
         }
     }
 
-    fn move_tile(&mut self, pos: Position, dx: isize, dy: isize) -> () {
-        let next_pos = Position {
-            x: (pos.x as isize + dx) as usize,
-            y: (pos.y as isize + dy) as usize,
-        };
-        match self.grid[next_pos.y][next_pos.x] {
+    fn move_tile(&mut self, pos: Point, dx: i32, dy: i32) {
+        let next_pos = pos + Vector::new(dx, dy);
+        let left = Point::new(next_pos.x - 1, next_pos.y);
+        let right = Point::new(next_pos.x + 1, next_pos.y);
+        match self.grid[next_pos] {
             Tile::Wall | Tile::Robot => { return; }
             Tile::Empty => {},
             Tile::Object => {
@@ -121,33 +81,33 @@ impl Warehouse {
                 if dx == -1 {
                     self.move_tile(next_pos, dx, dy);
                 } else if dx == 1 {
-                    self.move_tile( Position { x: next_pos.x + 1, y: next_pos.y }, dx, dy);
-                    self.grid[next_pos.y][next_pos.x + 1] = self.grid[next_pos.y][next_pos.x];
-                    self.grid[next_pos.y][next_pos.x] = Tile::Empty;
+                    self.move_tile(right, dx, dy);
+                    self.grid[right] = self.grid[next_pos];
+                    self.grid[next_pos] = Tile::Empty;
                 } else {
                     self.move_tile(next_pos, dx, dy);
-                    self.move_tile(Position { x: next_pos.x + 1, y: next_pos.y }, dx, dy);
-                    self.grid[next_pos.y][next_pos.x + 1] = self.grid[next_pos.y][next_pos.x];
-                    self.grid[next_pos.y][next_pos.x] = Tile::Empty;
+                    self.move_tile(right, dx, dy);
+                    self.grid[right] = self.grid[next_pos];
+                    self.grid[next_pos] = Tile::Empty;
                 }
             },
             Tile::RightBox => {
                 if dx == -1 {
-                    self.move_tile(Position { x: next_pos.x - 1, y: next_pos.y }, dx, dy);
-                    self.grid[next_pos.y][next_pos.x - 1] = self.grid[next_pos.y][next_pos.x];
-                    self.grid[next_pos.y][next_pos.x] = Tile::Empty;
+                    self.move_tile(left, dx, dy);
+                    self.grid[left] = self.grid[next_pos];
+                    self.grid[next_pos] = Tile::Empty;
                 } else if dx == 1 {
                     self.move_tile(next_pos, dx, dy);
                 } else {
-                    self.move_tile(Position { x: next_pos.x - 1, y: next_pos.y }, dx, dy);
+                    self.move_tile(left, dx, dy);
                     self.move_tile(next_pos, dx, dy);
-                    self.grid[next_pos.y][next_pos.x - 1] = self.grid[next_pos.y][next_pos.x];
-                    self.grid[next_pos.y][next_pos.x] = Tile::Empty;
+                    self.grid[left] = self.grid[next_pos];
+                    self.grid[next_pos] = Tile::Empty;
                 }
             },
         }
-        self.grid[next_pos.y][next_pos.x] = self.grid[pos.y][pos.x];
-        self.grid[pos.y][pos.x] = Tile::Empty;
+        self.grid[next_pos] = self.grid[pos];
+        self.grid[pos] = Tile::Empty;
     }
 
     fn simulate(&mut self, moves: &str) {
@@ -157,40 +117,34 @@ impl Warehouse {
     }
 
     fn calculate_gps_sum(&self) -> usize {
-        let mut score = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.grid[y][x] == Tile::LeftBox || self.grid[y][x] == Tile::Object {
-                    score += 100 * y + x;
-                }
-            }
-        }
-        score
+        self.grid
+            .iter()
+            .filter(|(_, &tile)| tile == Tile::LeftBox || tile == Tile::Object)
+            .map(|(pos, _)| 100 * pos.y as usize + pos.x as usize)
+            .sum()
     }
 
     fn scale_width(&mut self) {
-        let mut new_grid = vec![vec![Tile::Empty; self.width * 2]; self.height];
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let tile = self.grid[y][x];
-                new_grid[y][x * 2 + 1] = match tile {
-                    Tile::Wall => {
-                        new_grid[y][x * 2] = Tile::Wall;
-                        Tile::Wall
-                    }
-                    Tile::Object => {
-                        new_grid[y][x * 2] = Tile::LeftBox;
-                        Tile::RightBox
-                    }
-                    _ => Tile::Empty,
-                };
-                if tile == Tile::Robot {
-                    self.robot = Position { x: x * 2, y };
-                    new_grid[y][x * 2] = Tile::Robot;
+        let mut new_grid = Grid::new(self.grid.width() * 2, self.grid.height(), Tile::Empty);
+        for (pos, &tile) in self.grid.iter() {
+            let left = Point::new(pos.x * 2, pos.y);
+            let right = Point::new(pos.x * 2 + 1, pos.y);
+            new_grid[right] = match tile {
+                Tile::Wall => {
+                    new_grid[left] = Tile::Wall;
+                    Tile::Wall
+                }
+                Tile::Object => {
+                    new_grid[left] = Tile::LeftBox;
+                    Tile::RightBox
                 }
+                _ => Tile::Empty,
+            };
+            if tile == Tile::Robot {
+                self.robot = left;
+                new_grid[left] = Tile::Robot;
             }
         }
-        self.width *= 2;
         self.grid = new_grid;
     }
 }
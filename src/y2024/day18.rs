@@ -1,6 +1,7 @@
-use std::collections::HashSet;
 use crate::utils::grid::Point;
-use crate::utils::bfs::find_shortest_path;
+use crate::utils::bfs::find_shortest_path_with;
+use crate::utils::union_find::first_disconnecting;
+use crate::utils::visited::BitGrid;
 
 fn parse_input(input: &str) -> Vec<Point> {
     input
@@ -14,42 +15,53 @@ fn parse_input(input: &str) -> Vec<Point> {
         .collect()
 }
 
-fn find_path(corrupted: &HashSet<Point>, max_coord: i32) -> Option<usize> {
+fn corrupt(points: &[Point], max_coord: i32) -> BitGrid {
+    let size = max_coord as usize + 1;
+    let mut corrupted = BitGrid::new(size, size);
+    for &p in points {
+        corrupted.insert(p);
+    }
+    corrupted
+}
+
+fn find_path(corrupted: &BitGrid, max_coord: i32) -> Option<usize> {
     let start = Point::new(0, 0);
     let target = Point::new(max_coord, max_coord);
     
-    find_shortest_path(
+    find_shortest_path_with(
         start,
-        &|p: &Point| *p == target,
-        &|p: &Point| p.neighbors_with_bounds(max_coord + 1, max_coord + 1)
-            .into_iter()
-            .filter(|p| !corrupted.contains(p))
-            .collect()
+        |p: &Point| *p == target,
+        |p: &Point| p.neighbors_with_bounds(max_coord + 1, max_coord + 1)
+            .filter(|&p| !corrupted.contains(p)),
+        &mut BitGrid::new(corrupted.width(), corrupted.height()),
     )
 }
 
 pub fn part1(input: &str) -> usize {
     let points = parse_input(input);
-    let corrupted: HashSet<_> = points.iter().take(1024).copied().collect();
+    let corrupted = corrupt(&points[..1024], 70);
     find_path(&corrupted, 70).expect("No path found")
 }
 
 pub fn part2(input: &str) -> Point {
     let points = parse_input(input);
     let max_coord = 70;
-
-    for (i, &point) in points.iter().enumerate() {
-        let corrupted: HashSet<_> = points.iter().take(i + 1).copied().collect();
-        if find_path(&corrupted, max_coord).is_none() {
-            return point;
-        }
-    }
-    unreachable!("Solution must exist")
+    let size = max_coord as usize + 1;
+    let first_blocking = first_disconnecting(
+        size,
+        size,
+        |_| false,
+        &points,
+        Point::new(0, 0),
+        Point::new(max_coord, max_coord),
+    );
+    points[first_blocking.expect("Solution must exist")]
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::bisect::first_true;
 
     #[test]
     fn test_part1() {
@@ -62,4 +74,36 @@ mod tests {
         let input = std::fs::read_to_string("input/day18.txt").unwrap();
         assert_eq!(part2(&input), Point { x: 41, y: 26 });
     }
+
+    #[test]
+    fn test_part2_bisect() {
+        // paths only get blocked as more bytes fall, so the byte count can be bisected
+        let input = std::fs::read_to_string("input/day18.txt").unwrap();
+        let points = parse_input(&input);
+        let blocked = first_true(0..points.len(), |i| find_path(&corrupt(&points[..=i], 70), 70).is_none());
+        assert_eq!(blocked.map(|i| points[i]), Some(part2(&input)));
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    fn bench_neighbors() {
+        let input = std::fs::read_to_string("input/day18.txt").unwrap();
+        let corrupted = corrupt(&parse_input(&input)[..1024], 70);
+        let target = Point::new(70, 70);
+        // same search and visited set as `find_path`, only collecting the neighbours first
+        let collected = || find_shortest_path_with(
+            Point::new(0, 0),
+            |p: &Point| *p == target,
+            |p: &Point| p.neighbors_with_bounds(71, 71)
+                .filter(|&p| !corrupted.contains(p))
+                .collect::<Vec<_>>(),
+            &mut BitGrid::new(71, 71),
+        );
+        crate::utils::bench::compare(
+            "day18 bfs",
+            200,
+            ("Vec neighbours", collected),
+            ("iterator neighbours", || find_path(&corrupted, 70)),
+        );
+    }
 }
@@ -1,35 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use crate::utils::trie::{AhoCorasick, Match};
 
-#[derive(Default)]
-struct TrieNode {
-    is_end: bool,
-    children: HashMap<char, TrieNode>,
-}
-
-impl TrieNode {
-    fn new() -> Self {
-        Self::default()
-    }
-
-    fn insert(&mut self, pattern: &str) {
-        let mut current = self;
-        for c in pattern.chars() {
-            current = current.children.entry(c).or_default();
-        }
-        current.is_end = true;
-    }
-}
-
-fn parse_input(input: &str) -> (TrieNode, Vec<String>) {
+fn parse_input(input: &str) -> (AhoCorasick, Vec<String>) {
     let mut parts = input.split("\n\n");
-    let mut trie = TrieNode::new();
-
-    // Insert patterns into trie
-    parts
-        .next()
-        .unwrap()
-        .split(", ")
-        .for_each(|pattern| trie.insert(pattern));
+    let towels = AhoCorasick::new(parts.next().unwrap().split(", "));
 
     let designs = parts
         .next()
@@ -38,84 +11,66 @@ fn parse_input(input: &str) -> (TrieNode, Vec<String>) {
         .map(|s| s.to_string())
         .collect();
 
-    (trie, designs)
+    (towels, designs)
 }
 
-fn can_make_pattern_with_cache(target: &str, trie: &TrieNode, cache: &mut HashSet<String>) -> bool {
-    if target.is_empty() {
-        return true;
-    }
-
-    if cache.contains(target) {
-        return false;
-    }
-
-    let mut current = trie;
-    let chars: Vec<char> = target.chars().collect();
-
-    for (i, &c) in chars.iter().enumerate() {
-        if let Some(next) = current.children.get(&c) {
-            if next.is_end && can_make_pattern_with_cache(&target[i + 1..], trie, cache) {
-                return true;
-            }
-            current = next;
-        } else {
-            break;
-        }
-    }
-
-    cache.insert(target.to_string());
-    false
+/// Towels of the design starting at each match
+fn towels_used<'a>(design: &'a str, matches: &[Match]) -> Vec<&'a str> {
+    matches.iter().map(|m| &design[m.start..m.end]).collect()
 }
 
-fn count_pattern_ways(target: &str, trie: &TrieNode, cache: &mut HashMap<String, i64>) -> i64 {
-    if target.is_empty() {
-        return 1;
-    }
-
-    if let Some(&count) = cache.get(target) {
-        return count;
-    }
-
-    let mut total = 0;
-    let mut current = trie;
-    let chars: Vec<char> = target.chars().collect();
+/// One arrangement of towels that makes the design
+pub fn find_arrangement<'a>(towels: &AhoCorasick, design: &'a str) -> Option<Vec<&'a str>> {
+    towels.segmentation(design.as_bytes()).map(|matches| towels_used(design, &matches))
+}
 
-    for (i, &c) in chars.iter().enumerate() {
-        if let Some(next) = current.children.get(&c) {
-            if next.is_end {
-                total += count_pattern_ways(&target[i + 1..], trie, cache);
-            }
-            current = next;
-        } else {
-            break;
-        }
-    }
+/// An arrangement that makes the design with as few towels as possible
+pub fn fewest_towels<'a>(towels: &AhoCorasick, design: &'a str) -> Option<Vec<&'a str>> {
+    towels.shortest_segmentation(design.as_bytes()).map(|matches| towels_used(design, &matches))
+}
 
-    cache.insert(target.to_string(), total);
-    total
+/// Every arrangement that makes the design, one at a time
+pub fn arrangements<'a>(towels: &AhoCorasick, design: &'a str) -> impl Iterator<Item = Vec<&'a str>> + 'a {
+    towels.segmentations(design.as_bytes()).map(move |matches| towels_used(design, &matches))
 }
 
 pub fn part1(input: &str) -> i64 {
-    let (trie, designs) = parse_input(input);
-    let mut cache = HashSet::new();
+    let (towels, designs) = parse_input(input);
 
     designs
         .iter()
-        .filter(|design| can_make_pattern_with_cache(design, &trie, &mut cache))
+        .filter(|design| towels.can_segment(design.as_bytes()))
         .count() as i64
 }
 
 pub fn part2(input: &str) -> i64 {
-    let (trie, designs) = parse_input(input);
-    let mut cache = HashMap::new();
+    let (towels, designs) = parse_input(input);
 
     designs
         .iter()
-        .map(|design| count_pattern_ways(design, &trie, &mut cache))
+        .map(|design| towels.count_segmentations(design.as_bytes()) as i64)
         .sum()
 }
 
+/// One line per design with its number of arrangements and the one with the fewest towels,
+/// for checking answers by hand
+pub fn explain(input: &str) -> String {
+    let (towels, designs) = parse_input(input);
+
+    designs
+        .iter()
+        .map(|design| match fewest_towels(&towels, design) {
+            Some(fewest) => format!(
+                "{design}: {} arrangements, fewest {}",
+                towels.count_segmentations(design.as_bytes()),
+                fewest.join("+")
+            ),
+            None => format!("{design}: impossible"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,6 +82,34 @@ mod tests {
         assert_eq!(part1(input), 6);
     }
 
+    #[test]
+    fn test_arrangements() {
+        let input =
+            "r, wr, b, g, bwu, rb, gb, br\n\nbrwrr\nbggr\ngbbr\nrrbgbr\nubwu\nbwurrg\nbrgr\nbbrgwb";
+        let (towels, designs) = parse_input(input);
+        for design in &designs {
+            let all: Vec<Vec<&str>> = arrangements(&towels, design).collect();
+            assert_eq!(all.len() as u64, towels.count_segmentations(design.as_bytes()));
+            assert!(all.iter().all(|towels| towels.concat() == *design));
+            assert_eq!(find_arrangement(&towels, design).is_some(), towels.can_segment(design.as_bytes()));
+            if let Some(fewest) = fewest_towels(&towels, design) {
+                assert_eq!(Some(fewest.len()), all.iter().map(|towels| towels.len()).min());
+            }
+        }
+        assert_eq!(fewest_towels(&towels, "brwrr").unwrap(), ["br", "wr", "r"]);
+        assert_eq!(find_arrangement(&towels, "bggr").unwrap(), ["b", "g", "g", "r"]);
+        assert_eq!(find_arrangement(&towels, "ubwu"), None);
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = "r, wr, b, g, bwu, rb, gb, br\n\nbrwrr\nubwu\nbwurrg";
+        assert_eq!(
+            explain(input),
+            "brwrr: 2 arrangements, fewest br+wr+r\nubwu: impossible\nbwurrg: 1 arrangements, fewest bwu+r+r+g"
+        );
+    }
+
     #[test]
     fn test_input() {
         let input = std::fs::read_to_string("input/day19.txt").unwrap();
@@ -1,50 +1,37 @@
-use std::collections::HashSet;
-use crate::utils::grid::{Point, parse_grid};
-use crate::utils::bfs::get_distances;
+use crate::utils::grid::{Grid, Point};
+use crate::utils::bfs::get_distances_with;
+use crate::utils::visited::BitGrid;
 
 #[derive(Debug)]
 struct Map {
-    grid: Vec<Vec<char>>,
+    grid: Grid<char>,
     start: Point,
     end: Point,
 }
 
 impl Map {
     fn parse(input: &str) -> Self {
-        let grid = parse_grid(input);
-        let mut start = Point::new(0, 0);
-        let mut end = Point::new(0, 0);
-        
-        for y in 0..grid.len() {
-            for x in 0..grid[0].len() {
-                match grid[y][x] {
-                    'S' => start = Point::new(x as i32, y as i32),
-                    'E' => end = Point::new(x as i32, y as i32),
-                    _ => continue,
-                }
-            }
-        }
-        
+        let grid = Grid::parse_chars(input);
+        let start = grid.find(|&c| c == 'S').unwrap_or(Point::new(0, 0));
+        let end = grid.find(|&c| c == 'E').unwrap_or(Point::new(0, 0));
+
         Map { grid, start, end }
     }
 
     fn is_valid(&self, pos: &Point) -> bool {
-        pos.x >= 0 && pos.y >= 0 && 
-        pos.x < self.grid[0].len() as i32 && 
-        pos.y < self.grid.len() as i32
+        self.grid.contains(*pos)
     }
 
     fn is_wall(&self, pos: &Point) -> bool {
-        self.grid[pos.y as usize][pos.x as usize] == '#'
+        self.grid[*pos] == '#'
     }
 
     fn walkable_positions(&self) -> Vec<(Point, i32)> {
-        get_distances(
+        get_distances_with(
             self.start,
             |pos| pos.neighbors()
-                .into_iter()
-                .filter(|p| self.is_valid(p) && !self.is_wall(p))
-                .collect()
+                .filter(|p| self.is_valid(p) && !self.is_wall(p)),
+            &mut BitGrid::for_grid(&self.grid),
         )
         .into_iter()
         .map(|(pos, dist)| (pos, dist as i32))
@@ -58,7 +45,7 @@ impl Map {
         for (pos1, dist1) in &walkable {
             for (pos2, dist2) in &walkable {
                 if pos1 == pos2 { continue; }
-                let manhattan_dist = (pos1.x - pos2.x).abs() + (pos1.y - pos2.y).abs();
+                let manhattan_dist = pos1.manhattan_distance(pos2);
                 if manhattan_dist <= max_chat_length {
                     if dist1 - dist2 - manhattan_dist >= min_savings {
                         unique_cheats += 1;
@@ -116,4 +103,28 @@ mod tests {
         println!("Day 20 - Part 2 - Cheats saving ≥100 picoseconds: {} and chat length <= 20", 
                 map.count_cheats_with_savings(100, 20));
     }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    fn bench_neighbors() {
+        let input = std::fs::read_to_string("input/day20.txt").expect("Input file should exist");
+        let map = Map::parse(&input);
+        // same search and visited set as `walkable_positions`, only collecting the neighbours first
+        let collected = || get_distances_with(
+            map.start,
+            |pos| pos.neighbors()
+                .filter(|p| map.is_valid(p) && !map.is_wall(p))
+                .collect::<Vec<_>>(),
+            &mut BitGrid::for_grid(&map.grid),
+        )
+        .into_iter()
+        .map(|(pos, dist)| (pos, dist as i32))
+        .collect::<Vec<_>>();
+        crate::utils::bench::compare(
+            "day20 bfs",
+            200,
+            ("Vec neighbours", collected),
+            ("iterator neighbours", || map.walkable_positions()),
+        );
+    }
 }
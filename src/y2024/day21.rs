@@ -1,13 +1,7 @@
 use std::collections::HashMap;
-use crate::utils::grid::Point;
+use crate::utils::grid::{Direction, Point};
 use crate::utils::bfs::find_all_paths;
-
-const BFS_DIRECTIONS: [(char, (i32, i32)); 4] = [
-    ('^', (0, -1)),
-    ('>', (1, 0)),
-    ('v', (0, 1)),
-    ('<', (-1, 0)),
-];
+use crate::utils::memo::Memo;
 
 fn init_keypad() -> HashMap<char, Point> {
     let mut keypad = HashMap::new();
@@ -49,14 +43,13 @@ fn get_command(input: &HashMap<char, Point>, start: char, end: char) -> Vec<Stri
     let paths = find_all_paths(
         start_pos,
         |pos| *pos == end_pos,
-        |pos| {
-            BFS_DIRECTIONS.iter()
-                .map(|(_, (dx, dy))| Point::new(pos.x + dx, pos.y + dy))
+        |&pos| {
+            Direction::ALL.iter()
+                .map(move |dir| pos + dir.vector())
                 .filter(|new_pos| {
                     *new_pos != blank_pos && 
                     input.values().any(|&button| button == *new_pos)
                 })
-                .collect()
         }
     );
 
@@ -64,12 +57,11 @@ fn get_command(input: &HashMap<char, Point>, start: char, end: char) -> Vec<Stri
         .map(|path| {
             let mut command = String::new();
             for window in path.windows(2) {
-                let dx = window[1].x - window[0].x;
-                let dy = window[1].y - window[0].y;
-                let dir = BFS_DIRECTIONS.iter()
-                    .find(|(_, (dx2, dy2))| *dx2 == dx && *dy2 == dy)
-                    .unwrap().0;
-                command.push(dir);
+                let step = window[1] - window[0];
+                let dir = Direction::ALL.iter()
+                    .find(|dir| dir.vector() == step)
+                    .unwrap();
+                command.push(dir.to_char());
             }
             command + "A"
         })
@@ -79,41 +71,42 @@ fn get_command(input: &HashMap<char, Point>, start: char, end: char) -> Vec<Stri
     all_paths
 }
 
+/// Presses needed to type `code` on `input` through `robot` layers of directional keypads.
+/// `memos[robot]` caches this layer's results by code and `memos[..robot]` the layers below.
 fn get_key_presses(
     input: &HashMap<char, Point>,
+    directions: &HashMap<char, Point>,
     code: &str,
-    robot: i64,
-    memo: &mut HashMap<(String, i64), i64>,
+    robot: usize,
+    memos: &mut [Memo<String, i64>],
 ) -> i64 {
-    let key = (code.to_string(), robot);
-    if let Some(&result) = memo.get(&key) {
-        return result;
-    }
-
-    let mut current = 'A';
-    let mut length: i64 = 0;
-
-    for c in code.chars() {
-        let moves = get_command(input, current, c);
-        if robot == 0 {
-            length += moves[0].len() as i64;
-        } else {
-            length += moves
-                .iter()
-                .map(|move_str| get_key_presses(&init_directions(), move_str, robot - 1, memo))
-                .min()
-                .unwrap_or(0);
+    let (lower, rest) = memos.split_at_mut(robot);
+    rest[0].get_or_compute(code, |_| {
+        let mut current = 'A';
+        let mut length: i64 = 0;
+
+        for c in code.chars() {
+            let moves = get_command(input, current, c);
+            if robot == 0 {
+                length += moves[0].len() as i64;
+            } else {
+                length += moves
+                    .iter()
+                    .map(|move_str| get_key_presses(directions, directions, move_str, robot - 1, lower))
+                    .min()
+                    .unwrap_or(0);
+            }
+            current = c;
         }
-        current = c;
-    }
 
-    memo.insert(key, length);
-    length
+        length
+    })
 }
 
 pub fn part1(input: &str) -> i64 {
     let keypad = init_keypad();
-    let mut memo = HashMap::new();
+    let directions = init_directions();
+    let mut memos = vec![Memo::new(); 3];
 
     input
         .trim()
@@ -121,14 +114,15 @@ pub fn part1(input: &str) -> i64 {
         .map(|code| {
             let numerical: String = code.chars().filter(|c| c.is_digit(10)).collect();
             let num = numerical.parse::<i64>().unwrap();
-            num * get_key_presses(&keypad, code, 2, &mut memo)
+            num * get_key_presses(&keypad, &directions, code, 2, &mut memos)
         })
         .sum()
 }
 
 pub fn part2(input: &str) -> i64 {
     let keypad = init_keypad();
-    let mut memo = HashMap::new();
+    let directions = init_directions();
+    let mut memos = vec![Memo::new(); 26];
 
     input
         .trim()
@@ -136,7 +130,7 @@ pub fn part2(input: &str) -> i64 {
         .map(|code| {
             let numerical: String = code.chars().filter(|c| c.is_digit(10)).collect();
             let num = numerical.parse::<i64>().unwrap();
-            num * get_key_presses(&keypad, code, 25, &mut memo)
+            num * get_key_presses(&keypad, &directions, code, 25, &mut memos)
         })
         .sum()
 }
@@ -145,6 +139,11 @@ pub fn part2(input: &str) -> i64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_example() {
+        assert_eq!(part1("029A\n980A\n179A\n456A\n379A"), 126384);
+    }
+
     #[test]
     fn test_input() {
         let input = std::fs::read_to_string("input/day21.txt").expect("Input file should exist");
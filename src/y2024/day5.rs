@@ -1,81 +1,52 @@
-use std::{cmp::Ordering, collections::{HashMap, HashSet}};
+use crate::utils::graph::Graph;
 
-fn parse_input(input: &str) -> (Vec<(i32, i32)>, Vec<Vec<i32>>) {
+/// Page ordering rules as edges `before -> after`, and the updates as page ids in that graph
+fn parse_input(input: &str) -> (Graph<i32>, Vec<Vec<usize>>) {
     let mut parts = input.split("\n\n");
-    
-    let rules: Vec<(i32, i32)> = parts
-        .next()
-        .expect("Missing rules section")
-        .lines()
-        .map(|line| {
-            let mut number_pairs = line.split('|');
-            (
-                number_pairs.next().expect("Missing first number").parse::<i32>().expect("Invalid first number"),
-                number_pairs.next().expect("Missing second number").parse::<i32>().expect("Invalid second number")
-            )
-        }).collect();
 
-    let print_orders = parts
+    let mut rules = Graph::directed();
+    for line in parts.next().expect("Missing rules section").lines() {
+        let (before, after) = line.split_once('|').expect("Invalid rule");
+        rules.add_edge(
+            before.parse::<i32>().expect("Invalid first number"),
+            after.parse::<i32>().expect("Invalid second number"),
+            (),
+        );
+    }
+
+    let updates = parts
         .next()
         .expect("Missing print orders section")
         .lines()
-        .map(|line| line.split(',').map(|s| s.parse::<i32>().expect("Invalid number")).collect())
+        .map(|line| {
+            line.split(',')
+                .map(|s| rules.add_node(s.parse::<i32>().expect("Invalid number")))
+                .collect()
+        })
         .collect();
 
-    (rules, print_orders)
+    (rules, updates)
 }
 
-fn is_ordered(rules: &Vec<(i32, i32)>, update: &Vec<i32>) -> bool {
-    let positions: HashMap<_, _> = update.iter()
-        .enumerate()
-        .map(|(i, &page)| (page, i))
-        .collect();
-    rules.iter()
-        .all(|(first, second)| {
-            match (positions.get(first), positions.get(second)) {
-                (Some(&f), Some(&s)) => f < s,
-                _ => true
-            }
-        })
+fn middle_page(rules: &Graph<i32>, update: &[usize]) -> i32 {
+    *rules.name(update[update.len() / 2])
 }
 
 fn part1(input: &str) -> i32 {
-    let (rules, orders) = parse_input(input);
-    let mut result = 0;
-    for o in orders.iter() {
-        if is_ordered(&rules, o) {
-            result += o[o.len() / 2];
-        }
-    }
-    result
-}
-
-fn fix_ordering(rules: &HashMap<i32, HashSet<i32>>, update: &mut Vec<i32>) {
-    update.sort_by(|&a, &b| {
-        if rules.get(&a).map_or(false, |set| set.contains(&b)) {
-            Ordering::Less
-        } else if rules.get(&b).map_or(false, |set| set.contains(&a)) {
-            Ordering::Greater
-        } else {
-            Ordering::Equal
-        }
-    });
+    let (rules, updates) = parse_input(input);
+    updates.iter()
+        .filter(|update| rules.satisfies(update))
+        .map(|update| middle_page(&rules, update))
+        .sum()
 }
 
 fn part2(input: &str) -> i32 {
-    let (rules, mut updates) = parse_input(input);
-    let rules_map: HashMap<i32, HashSet<i32>> = rules.iter()
-        .fold(HashMap::new(), |mut map, &(l, r)| {
-            map.entry(l)
-                .or_insert(HashSet::new())
-                .insert(r);
-            map
-        });
-    updates.iter_mut()
-        .filter(|update| !is_ordered(&rules, update))
+    let (rules, updates) = parse_input(input);
+    updates.iter()
+        .filter(|update| !rules.satisfies(update))
         .map(|update| {
-            fix_ordering(&rules_map, update);
-            update[update.len() / 2]
+            let fixed = rules.topological_sort_subset(update).expect("Rules for an update form a cycle");
+            middle_page(&rules, &fixed)
         })
         .sum()
 }
@@ -84,6 +55,53 @@ fn part2(input: &str) -> i32 {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part1(EXAMPLE), 143);
+        assert_eq!(part2(EXAMPLE), 123);
+    }
+
+    #[test]
+    fn test_broken_rules() {
+        let (rules, updates) = parse_input(EXAMPLE);
+        let broken: Vec<_> = rules.violations(&updates[3])
+            .into_iter()
+            .map(|(before, after)| (*rules.name(before), *rules.name(after)))
+            .collect();
+        assert_eq!(broken, vec![(97, 75)]);
+        assert!(rules.violations(&updates[0]).is_empty());
+    }
+
     #[test]
     fn test_part1() {
         let input = std::fs::read_to_string("input/day5.txt").unwrap();